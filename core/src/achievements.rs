@@ -0,0 +1,44 @@
+use crate::{settings::Catalogue, variables::Variables};
+
+/// Both token recognition arrays together.
+pub const ACHIEVEMENT_COUNT: usize = 100;
+/// How many achievements each token recognition array holds.
+const PER_ARRAY: usize = 50;
+/// Each entry in the token recognition arrays is either this for achieved or `b'0'` for not.
+const EARNED: u8 = 0x31;
+
+/// The game doesn't keep achievement names anywhere we can read, so they're numbered in the order it stores them.
+/// Like chapters, the runner sees them numbered from 1 while indices start at 0.
+pub fn name(index: usize) -> String {
+    format!("Token Recognition #{}", index + 1)
+}
+
+/// Indices of every achievement that was earned this tick.
+pub fn unlocked(vars: &Variables) -> Vec<usize> {
+    [vars.token_recognitions, vars.token_recognitions_two]
+        .iter()
+        .enumerate()
+        .filter_map(|(array, tokens)| tokens.map(|tokens| (array, tokens)))
+        .flat_map(|(array, tokens)| {
+            (0..PER_ARRAY)
+                .filter(|&i| tokens.old[i] != EARNED && tokens.current[i] == EARNED)
+                .map(move |i| array * PER_ARRAY + i)
+        })
+        .collect()
+}
+
+pub struct Achievements;
+impl Catalogue for Achievements {
+    const KEY_PREFIX: &'static str = "split_on_achievement_";
+
+    fn entries() -> Vec<(String, String)> {
+        (0..ACHIEVEMENT_COUNT)
+            .map(|index| {
+                (
+                    (index + 1).to_string(),
+                    format!("Split when earning {}", name(index)),
+                )
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,92 @@
+//! Replays a trace through the splitter and prints what it did to the timer.
+//!
+//! `replay <trace> [setting[=value]]...`
+//! The trace can be a whole log with trace lines in it. Settings not given keep their defaults.
+
+use dt3_core::{
+    replay,
+    settings::{SettingsStore, SplitterSettings},
+    trace,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    env, fs,
+    process::ExitCode,
+};
+
+/// Learns which settings exist, and whether they're checkboxes or text, from registering them.
+#[derive(Default)]
+struct KnownSettings {
+    bools: HashSet<String>,
+    texts: HashSet<String>,
+}
+impl SettingsStore for KnownSettings {
+    fn add_bool(&mut self, key: &str, _description: &str, _default: bool) {
+        self.bools.insert(key.to_owned());
+    }
+
+    fn get_bool(&self, _key: &str) -> Option<bool> {
+        None
+    }
+
+    fn add_text(&mut self, key: &str, _description: &str, _default: &str) {
+        self.texts.insert(key.to_owned());
+    }
+
+    fn get_text(&self, _key: &str) -> Option<String> {
+        None
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let Some(path) = args.next() else {
+        eprintln!("Usage: replay <trace> [setting[=value]]...");
+        return ExitCode::FAILURE;
+    };
+
+    let mut known = KnownSettings::default();
+    SplitterSettings::register(&mut known);
+
+    let mut settings = HashMap::new();
+    for arg in args {
+        let (key, value) = arg.split_once('=').unwrap_or((&arg, "true"));
+        if known.bools.contains(key) {
+            if value.parse::<bool>().is_err() {
+                eprintln!("`{}` has to be true or false", arg);
+                return ExitCode::FAILURE;
+            }
+        } else if !known.texts.contains(key) {
+            eprintln!("There's no setting called `{}`", key);
+            return ExitCode::FAILURE;
+        }
+        settings.insert(key.to_owned(), value.to_owned());
+    }
+    let settings = SplitterSettings::load(&settings);
+
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) => {
+            eprintln!("Couldn't read {}: {}", path, err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let frames = match trace::parse(&text) {
+        Ok(frames) => frames,
+        Err(err) => {
+            eprintln!("Couldn't read the trace: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let report = replay::replay(&frames, settings);
+    for (tick, message) in &report.messages {
+        println!("{:>8}   {}", tick, message);
+    }
+    println!();
+    for (tick, action) in &report.actions {
+        println!("{:>8}   {}", tick, action);
+    }
+
+    ExitCode::SUCCESS
+}
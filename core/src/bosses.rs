@@ -0,0 +1,4 @@
+/// Whether `name` is one of the bosses picked for `split_on_bosses`, one per line exactly as the game writes it to `currentBossName`.
+pub fn listed(bosses: &str, name: &str) -> bool {
+    bosses.lines().map(str::trim).any(|boss| boss == name)
+}
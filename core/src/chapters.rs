@@ -0,0 +1,78 @@
+use crate::{settings::Catalogue, variables::Variables};
+
+pub struct Chapter {
+    pub name: &'static str,
+    /// the gameProgress value the chapter starts at
+    pub progress: f64,
+}
+
+impl Chapter {
+    const fn new(name: &'static str, progress: f64) -> Self {
+        Self { name, progress }
+    }
+}
+
+/// There isn't a good way to detect chapters, so this is a manually defined map of gameProgress values.
+pub const CHAPTERS: [Chapter; 21] = [
+    Chapter::new("Ch1", 0.0),
+    Chapter::new("Gate 1 Mario", 160.0),
+    Chapter::new("Ch3", 530.0),
+    Chapter::new("Gate 2 Zelda", 630.0),
+    Chapter::new("Ch5", 930.0),
+    Chapter::new("Gate 3 Castlevania", 1160.0),
+    Chapter::new("Ch7", 1540.0),
+    Chapter::new("Gate 4 Megaman", 1770.0),
+    Chapter::new("Ch9", 2010.0),
+    Chapter::new("The Vault", 2170.0),
+    Chapter::new("Ch11", 2570.0),
+    Chapter::new("Ch12", 2600.0),
+    Chapter::new("Gate 5 Metroid", 2680.0),
+    Chapter::new("Ch14", 3090.0),
+    Chapter::new("Gate 6 RPG", 3400.0),
+    Chapter::new("Ch16", 3880.0),
+    Chapter::new("Ch17", 3940.0),
+    Chapter::new("Ch18", 4210.0),
+    Chapter::new("Ch19", 4530.0),
+    Chapter::new("Ch20", 5050.0),
+    Chapter::new("Ch21", 5510.0),
+];
+
+/// Indices of every chapter whose start was crossed this tick, in order.
+/// gameProgress can jump past a breakpoint instead of landing on it, or past several at once.
+/// Going backwards, like when loading an earlier save, never counts.
+/// The first chapter starts along with the run at 0 progress, so it's never reached this way.
+pub fn reached(vars: &Variables) -> Vec<usize> {
+    let Some(progress) = vars.game_progress.filter(|x| x.current > x.old) else {
+        return Vec::new();
+    };
+
+    CHAPTERS
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(_, chapter)| {
+            progress.old < chapter.progress && chapter.progress <= progress.current
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
+pub struct Chapters;
+/// Every chapter but the first, which already has the timer start. Entry `i` is chapter index `i + 1`.
+impl Catalogue for Chapters {
+    const KEY_PREFIX: &'static str = "split_on_chapter_";
+
+    fn entries() -> Vec<(String, String)> {
+        CHAPTERS
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(index, chapter)| {
+                (
+                    (index + 1).to_string(),
+                    format!("Split when {} starts", chapter.name),
+                )
+            })
+            .collect()
+    }
+}
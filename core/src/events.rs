@@ -0,0 +1,90 @@
+use crate::{
+    achievements, chapters,
+    maps::{MAP_ID_INTRO_SCENE, MAP_ID_TITLE_SCREEN},
+    variables::Variables,
+};
+
+/// Something that happened in the game this tick, worked out once from the raw variables
+/// so starting, splitting and resetting all agree on what happened.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent<'a> {
+    /// the room changed
+    MapEntered { from: i32, to: i32 },
+    /// `currentBossName` was set
+    BossStarted { name: &'a str, in_gallery: bool },
+    /// `currentBossName` was cleared
+    BossDefeated { name: &'a str, in_gallery: bool },
+    /// index into `CHAPTERS`, one event per chapter crossed
+    ChapterReached(usize),
+    /// the program chip count went up
+    ChipCollected { from: f64, to: f64 },
+    /// index into the combined token recognition arrays
+    AchievementUnlocked(usize),
+    /// the item percentage changed, either way
+    PercentChanged { from: f64, to: f64 },
+    /// `gameCompleted` was set right before the epilogue returns to the title
+    GameCompleted,
+    /// new game was picked on the title screen, which always leads to the intro scene with no progress
+    NewGameStarted,
+}
+
+/// Turns one tick's variables into everything that happened, in the order it's listed in `GameEvent`.
+pub fn collect<'a>(vars: &Variables<'a>) -> Vec<GameEvent<'a>> {
+    let mut events = Vec::new();
+
+    if let Some(map_id) = vars.map_id.filter(|x| x.old != x.current) {
+        events.push(GameEvent::MapEntered {
+            from: map_id.old,
+            to: map_id.current,
+        });
+    }
+    if let Some(name) = vars.started_boss() {
+        events.push(GameEvent::BossStarted {
+            name,
+            in_gallery: vars.in_boss_gallery(),
+        });
+    }
+    if let Some(name) = vars.defeated_boss() {
+        events.push(GameEvent::BossDefeated {
+            name,
+            in_gallery: vars.in_boss_gallery(),
+        });
+    }
+    events.extend(
+        chapters::reached(vars)
+            .into_iter()
+            .map(GameEvent::ChapterReached),
+    );
+    if let Some(chips) = vars.program_chips.filter(|x| x.current > x.old) {
+        events.push(GameEvent::ChipCollected {
+            from: chips.old,
+            to: chips.current,
+        });
+    }
+    events.extend(
+        achievements::unlocked(vars)
+            .into_iter()
+            .map(GameEvent::AchievementUnlocked),
+    );
+    if let Some(percent) = vars.game_percent.filter(|x| x.old != x.current) {
+        events.push(GameEvent::PercentChanged {
+            from: percent.old,
+            to: percent.current,
+        });
+    }
+    if vars.game_completed.is_some_and(|x| x.check(|&x| x == 1.0)) {
+        events.push(GameEvent::GameCompleted);
+    }
+    // map 1 is the title screen, and map 43 is the intro cutscene on that cliff
+    // it's the first thing that appears after you press new game and can only be reached from that
+    // however let's safeguard with game_progress of 0 as well in case weird stuff happens
+    if vars
+        .map_id
+        .is_some_and(|x| x.old == MAP_ID_TITLE_SCREEN && x.current == MAP_ID_INTRO_SCENE)
+        && vars.game_progress.is_some_and(|x| x.current == 0.0)
+    {
+        events.push(GameEvent::NewGameStarted);
+    }
+
+    events
+}
@@ -0,0 +1,72 @@
+use std::collections::HashSet;
+
+/// Something that should only ever split once per run.
+#[derive(PartialEq, Eq, Hash)]
+pub enum Milestone {
+    /// the first visit to a map
+    Map(i32),
+    /// fighting a boss, which happens again on every retry
+    BossStarted {
+        name: String,
+        in_gallery: bool,
+    },
+    BossDefeated {
+        name: String,
+        in_gallery: bool,
+    },
+    /// index into `CHAPTERS`
+    Chapter(usize),
+    /// index into the combined token recognition arrays
+    Achievement(usize),
+    /// index into `MAP_TRANSITIONS`, for the ones that only split once
+    MapTransition(usize),
+    /// index into the transitions from `custom_map_transitions`, for the ones that only split once
+    CustomMapTransition(usize),
+}
+
+/// The highest a value has been this run, so every step below it only splits once
+/// no matter how far a single change jumps or whether an older save gets loaded.
+#[derive(Default)]
+pub struct HighWaterMark {
+    best: Option<f64>,
+}
+impl HighWaterMark {
+    /// Forgets the run, the value before the next change becomes the starting point.
+    pub fn reset(&mut self) {
+        self.best = None;
+    }
+
+    /// The part of going from `from` to `to` that's above the highest value so far, as `(highest, to)`.
+    pub fn raise(&mut self, from: f64, to: f64) -> Option<(f64, f64)> {
+        let best = *self.best.get_or_insert(from);
+        if to <= best {
+            return None;
+        }
+
+        self.best = Some(to);
+        Some((best, to))
+    }
+}
+
+/// Everything that already produced a split this run, so retries, revisits and older saves don't split again.
+/// Cleared whenever the timer isn't running, so a reset always starts from nothing.
+#[derive(Default)]
+pub struct RunLedger {
+    reached: HashSet<Milestone>,
+    /// the most program chips we've had, since every count below it already split
+    pub chips: HighWaterMark,
+    /// the highest item percentage we've had, since every threshold below it already split
+    pub percent: HighWaterMark,
+}
+impl RunLedger {
+    pub fn clear(&mut self) {
+        self.reached.clear();
+        self.chips.reset();
+        self.percent.reset();
+    }
+
+    /// Records the milestone, returning whether this is the first time it happened this run.
+    pub fn first(&mut self, milestone: Milestone) -> bool {
+        self.reached.insert(milestone)
+    }
+}
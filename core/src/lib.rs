@@ -0,0 +1,22 @@
+//! Everything the autosplitter decides, kept apart from the game process and the autosplitting runtime
+//! so it builds and runs on the host as well as in wasm.
+
+pub mod achievements;
+pub mod bosses;
+pub mod chapters;
+pub mod events;
+pub mod ledger;
+pub mod maps;
+pub mod memory;
+pub mod percent;
+pub mod pointers;
+pub mod program_chips;
+pub mod replay;
+pub mod route;
+pub mod settings;
+pub mod splitter;
+pub mod state;
+pub mod timer;
+pub mod trace;
+pub mod variables;
+pub mod watcher;
@@ -0,0 +1,139 @@
+use std::{borrow::Cow, fmt};
+
+use crate::settings::Catalogue;
+
+/// map 1 is the title screen
+pub const MAP_ID_TITLE_SCREEN: i32 = 1;
+/// the intro cutscene on that cliff, it's the first thing that appears after you press new game
+pub const MAP_ID_INTRO_SCENE: i32 = 43;
+pub const MAP_ID_SACRED_TEMPLE: i32 = 236;
+pub const MAP_ID_SACRED_TEMPLE_EXIT_MAP: i32 = 245;
+
+/// Names for the internal gamemaker room ids we know about.
+/// Rooms missing from here still work everywhere, they're just shown by id.
+pub const MAPS: &[(i32, &str)] = &[
+    (MAP_ID_TITLE_SCREEN, "Title Screen"),
+    (MAP_ID_INTRO_SCENE, "Intro Scene"),
+    (MAP_ID_SACRED_TEMPLE, "Sacred Temple"),
+    (MAP_ID_SACRED_TEMPLE_EXIT_MAP, "Sacred Temple Exit"),
+];
+
+/// A readable name for a map, for the logs.
+pub fn name(id: i32) -> String {
+    match MAPS.iter().find(|&&(known, _)| known == id) {
+        Some((_, name)) => format!("{} ({})", name, id),
+        None => format!("Map {}", id),
+    }
+}
+
+/// Finds a map id from either its name (ignoring case) or the id itself.
+pub fn find(name_or_id: &str) -> Option<i32> {
+    MAPS.iter()
+        .find(|(_, name)| name.eq_ignore_ascii_case(name_or_id))
+        .map(|&(id, _)| id)
+        .or_else(|| name_or_id.parse().ok())
+}
+
+/// A split on moving from one map into another.
+pub struct MapTransition {
+    /// the map we have to come from, or any map if `None`
+    pub from: Option<i32>,
+    pub to: i32,
+    /// maps that don't count as coming from, like the exit map of the place we're entering
+    pub excluded_from: Cow<'static, [i32]>,
+    /// only split the first time this happens in a run, instead of every time
+    pub once: bool,
+}
+impl MapTransition {
+    /// Whether going from the map `from` to `to` is this transition.
+    pub fn check(&self, from: i32, to: i32) -> bool {
+        let from_matches = match self.from {
+            Some(expected) => from == expected,
+            None => true,
+        };
+        from_matches && from != to && to == self.to && !self.excluded_from.contains(&from)
+    }
+
+    /// Reads a transition written as `[once ][from>]to[!excluded,excluded...]`, where maps are ids or names.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let (once, text) = match text.trim_start().strip_prefix("once ") {
+            Some(text) => (true, text),
+            None => (false, text),
+        };
+        let map =
+            |name: &str| find(name.trim()).ok_or_else(|| format!("Unknown map `{}`", name.trim()));
+
+        let (maps, excluded) = text.split_once('!').unwrap_or((text, ""));
+        let (from, to) = match maps.split_once('>') {
+            Some((from, to)) => (Some(map(from)?), map(to)?),
+            None => (None, map(maps)?),
+        };
+        let excluded_from = excluded
+            .split(',')
+            .filter(|name| !name.trim().is_empty())
+            .map(map)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            from,
+            to,
+            excluded_from: excluded_from.into(),
+            once,
+        })
+    }
+}
+impl fmt::Display for MapTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(from) = self.from {
+            write!(f, "{} > ", name(from))?;
+        }
+        write!(f, "{}", name(self.to))?;
+        for (i, &excluded) in self.excluded_from.iter().enumerate() {
+            let separator = if i == 0 { " except from " } else { ", " };
+            write!(f, "{}{}", separator, name(excluded))?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads one transition per line, skipping empty lines and lines starting with `#`.
+pub fn parse_transitions(text: &str) -> Result<Vec<MapTransition>, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(MapTransition::parse)
+        .collect()
+}
+
+pub struct MapTransitionSetting {
+    /// the whole settings key
+    pub key: &'static str,
+    pub description: &'static str,
+    pub transition: MapTransition,
+}
+
+/// Every map transition that can be turned on in the settings.
+/// Anything else a route needs can go in the `custom_map_transitions` setting.
+pub const MAP_TRANSITIONS: &[MapTransitionSetting] = &[MapTransitionSetting {
+    key: "split_on_sacred_temple",
+    description: "Split when entering the sacred temple\nDoesn't include entries from the exit map",
+    transition: MapTransition {
+        from: None,
+        to: MAP_ID_SACRED_TEMPLE,
+        excluded_from: Cow::Borrowed(&[MAP_ID_SACRED_TEMPLE_EXIT_MAP]),
+        once: false,
+    },
+}];
+
+pub struct MapTransitions;
+impl Catalogue for MapTransitions {
+    // the keys are written out in full so the sacred temple one keeps its original key
+    const KEY_PREFIX: &'static str = "";
+
+    fn entries() -> Vec<(String, String)> {
+        MAP_TRANSITIONS
+            .iter()
+            .map(|setting| (setting.key.to_owned(), setting.description.to_owned()))
+            .collect()
+    }
+}
@@ -0,0 +1,103 @@
+use bytemuck::Pod;
+use std::collections::HashMap;
+
+/// A read failed, usually because nothing is mapped at the address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadError;
+
+/// Somewhere we can read the game's memory from, the real process or a fake one.
+pub trait MemorySource {
+    /// Fills `buf` with the memory starting at `address`, failing if any of it can't be read.
+    fn read_into_buf(&self, address: u64, buf: &mut [u8]) -> Result<(), ReadError>;
+
+    fn read<T: Pod>(&self, address: u64) -> Result<T, ReadError> {
+        let mut value = T::zeroed();
+        self.read_into_buf(address, bytemuck::bytes_of_mut(&mut value))?;
+        Ok(value)
+    }
+
+    /// Follows a path of 32 bit pointers starting at `base`, adding each offset before dereferencing.
+    /// The last offset is added to the final pointer to find the value. An empty path never reads.
+    fn read_pointer_path32<T: Pod>(&self, base: u32, path: &[u32]) -> Result<T, ReadError> {
+        let (&last, path) = path.split_last().ok_or(ReadError)?;
+        let mut address = base as u64;
+        for &offset in path {
+            address = self.read::<u32>(address + offset as u64)? as u64;
+        }
+        self.read(address + last as u64)
+    }
+}
+
+/// Where `FakeMemory` puts pointers it makes up, far from anything a test is likely to write by hand.
+const FAKE_ALLOCATION_START: u32 = 0x1000_0000;
+/// How much room every made up pointer gets.
+const FAKE_ALLOCATION_SIZE: u32 = 0x10000;
+
+/// Made up game memory, so the splitter can run without the game.
+/// Only bytes that were written can be read back, everything else acts unmapped.
+#[derive(Debug, Clone)]
+pub struct FakeMemory {
+    bytes: HashMap<u64, u8>,
+    /// where the next pointer made up by `write_pointer_path32` points
+    next_allocation: u32,
+}
+impl Default for FakeMemory {
+    fn default() -> Self {
+        Self {
+            bytes: HashMap::new(),
+            next_allocation: FAKE_ALLOCATION_START,
+        }
+    }
+}
+impl FakeMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write(&mut self, address: u64, bytes: &[u8]) {
+        for (i, &byte) in bytes.iter().enumerate() {
+            self.bytes.insert(address + i as u64, byte);
+        }
+    }
+
+    pub fn write_value<T: Pod>(&mut self, address: u64, value: T) {
+        self.write(address, bytemuck::bytes_of(&value));
+    }
+
+    /// Makes `read_pointer_path32(base, path)` return `value`.
+    /// Pointers along the path that are already written are followed, missing ones are made up.
+    pub fn write_pointer_path32<T: Pod>(&mut self, base: u32, path: &[u32], value: T) {
+        let Some((&last, path)) = path.split_last() else {
+            return;
+        };
+        let mut address = base as u64;
+        for &offset in path {
+            let slot = address + offset as u64;
+            address = match self.read::<u32>(slot) {
+                Ok(pointer) => pointer as u64,
+                Err(_) => {
+                    let pointer = self.next_allocation;
+                    self.next_allocation += FAKE_ALLOCATION_SIZE;
+                    self.write_value(slot, pointer);
+                    pointer as u64
+                }
+            };
+        }
+        self.write_value(address + last as u64, value);
+    }
+
+    /// Unmaps the memory, like the game freeing it.
+    pub fn remove(&mut self, address: u64, len: u64) {
+        for address in address..address + len {
+            self.bytes.remove(&address);
+        }
+    }
+}
+impl MemorySource for FakeMemory {
+    fn read_into_buf(&self, address: u64, buf: &mut [u8]) -> Result<(), ReadError> {
+        for (i, byte) in buf.iter_mut().enumerate() {
+            *byte = *self.bytes.get(&(address + i as u64)).ok_or(ReadError)?;
+        }
+        Ok(())
+    }
+}
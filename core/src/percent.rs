@@ -0,0 +1,79 @@
+use crate::{settings::SplitterSettings, splitter::SplitReason};
+
+/// `gamePercent` is a double, so allow a little slack for values like 99.99999 that are meant to be 100.
+const PERCENT_EPSILON: f64 = 0.001;
+
+/// Reads one item percentage, like `33.3`.
+pub fn parse(text: &str) -> Result<f64, String> {
+    match text.trim().parse::<f64>() {
+        Ok(percent) if percent > 0.0 && percent <= 100.0 => Ok(percent),
+        _ => Err(format!(
+            "`{}` isn't a percentage above 0 and up to 100",
+            text.trim()
+        )),
+    }
+}
+
+/// Reads item percentages separated by commas or lines, in any order.
+pub fn parse_list(text: &str) -> Result<Vec<f64>, String> {
+    text.split([',', '\n'])
+        .filter(|percent| !percent.trim().is_empty())
+        .map(parse)
+        .collect()
+}
+
+/// Every item percentage split turned on in the settings, sorted, with every setting that asked for it.
+/// Each percentage only shows up once, so several settings wanting it still make one split.
+pub fn thresholds(
+    settings: &SplitterSettings,
+    percentages: &[f64],
+) -> Vec<(f64, Vec<SplitReason>)> {
+    let mut wanted = Vec::new();
+    if settings.split_every_ten_percent {
+        wanted.extend(
+            (10..=100)
+                .step_by(10)
+                .map(|percent| (percent as f64, SplitReason::EveryTenPercent)),
+        );
+    }
+    if settings.split_at_100_percent {
+        wanted.push((100.0, SplitReason::HundredPercent));
+    }
+    wanted.extend(
+        percentages
+            .iter()
+            .map(|&percent| (percent, SplitReason::Percent)),
+    );
+    wanted.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+    let mut thresholds: Vec<(f64, Vec<SplitReason>)> = Vec::new();
+    for (percent, reason) in wanted {
+        match thresholds.last_mut() {
+            Some((last, reasons)) if percent - *last < PERCENT_EPSILON => {
+                if !reasons.contains(&reason) {
+                    reasons.push(reason);
+                }
+            }
+            _ => thresholds.push((percent, vec![reason])),
+        }
+    }
+    thresholds
+}
+
+/// Whether the item percentage reached `threshold` going from `from` to `to`.
+pub fn crossed(from: f64, to: f64, threshold: f64) -> bool {
+    from + PERCENT_EPSILON < threshold && threshold <= to + PERCENT_EPSILON
+}
+
+/// Every threshold reached going from `from` to `to`, in order.
+pub fn reached(
+    from: f64,
+    to: f64,
+    thresholds: &[(f64, Vec<SplitReason>)],
+) -> Vec<(f64, Vec<SplitReason>)> {
+    thresholds
+        .iter()
+        .filter(|&&(threshold, _)| crossed(from, to, threshold))
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,78 @@
+use crate::timer::Logger;
+
+/// Where to find every variable we read from one build of the game.
+/// Global variables all live in the gamemaker globals instance, and are listed by their name in the game.
+pub struct PointerTable {
+    /// static offset of the current room id
+    pub map_id: u32,
+    /// static offset of the pointer to the globals instance
+    /// Only used when `globals_signature` can't find it.
+    pub globals: u32,
+    /// pattern matching the runner loading the globals instance pointer
+    pub globals_signature: &'static str,
+    /// where in `globals_signature` the absolute address of the globals pointer is
+    pub globals_signature_offset: u32,
+    /// offset from the globals instance to its variable array
+    pub globals_vars: u32,
+    /// offsets into the globals array for every global variable we read, by name
+    pub global_offsets: &'static [(&'static str, u32)],
+}
+
+impl PointerTable {
+    /// Finds the offset of a global variable in the globals array.
+    pub fn global_offset(&self, name: &str, log: &mut impl Logger) -> Option<u32> {
+        let offset = self
+            .global_offsets
+            .iter()
+            .find(|(known, _)| *known == name)
+            .map(|&(_, offset)| offset);
+        if offset.is_none() {
+            log.print_message(&format!("Don't know where the global `{}` is", name));
+        }
+        offset
+    }
+
+    /// Picks the absolute address of the globals pointer out of every plausible `globals_signature` match.
+    /// A match at the static offset confirms it. Otherwise only a single match is trusted,
+    /// since several mean the signature also hits unrelated code and any of them could be wrong.
+    pub fn pick_globals(&self, module_base: u32, candidates: &[u32]) -> Result<u32, String> {
+        let mut candidates = candidates.to_vec();
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let expected = module_base.wrapping_add(self.globals);
+        match candidates[..] {
+            _ if candidates.contains(&expected) => Ok(expected),
+            [globals] => Ok(globals),
+            [] => Err("the signature didn't match anything".to_owned()),
+            _ => Err(format!(
+                "the signature matched {} different pointers",
+                candidates.len()
+            )),
+        }
+    }
+}
+
+/// Everything we read from v1.5.2.4, the build everyone runs on.
+pub const V1_5_2_4: PointerTable = PointerTable {
+    map_id: 0x4452FC,
+    globals: 0x286AB4,
+    // mov eax, [globals]; mov eax, [eax+4]
+    globals_signature: "A1 ?? ?? ?? ?? 8B 40 04",
+    globals_signature_offset: 0x1,
+    globals_vars: 0x4,
+    global_offsets: &[
+        ("gameProgress", 0x4890),
+        ("programChips", 0x48B8),
+        ("gamePercent", 0x48E0),
+        ("gameCompleted", 0x5498),
+        ("tokenRecognitions", 0x54C8),
+        ("tokenRecognitionsTwo", 0x54F0),
+        ("inCutscene", 0x4CA0),
+        ("inGameOver", 0x4C50),
+        ("isPaused", 0x6A50),
+        ("currentBossName", 0x4D98),
+        ("bossTrack", 0x4DB8),
+        ("bossGallery", 0x5240),
+    ],
+};
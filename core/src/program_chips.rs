@@ -0,0 +1,33 @@
+use crate::settings::Catalogue;
+
+/// Highest program chip count that can be picked as a split.
+pub const MAX_PROGRAM_CHIPS: u32 = 10;
+
+/// Whether a chip count read from memory could be real.
+/// Memory read in the middle of a room transition can be garbage, like `NaN` or billions of chips.
+pub fn plausible(count: f64) -> bool {
+    count.is_finite() && (0.0..=MAX_PROGRAM_CHIPS as f64).contains(&count)
+}
+
+/// Every chip count reached going from `from` to `to` chips, in order.
+/// Never goes past `MAX_PROGRAM_CHIPS`, whatever the counts are.
+pub fn reached(from: f64, to: f64) -> Vec<u32> {
+    let clamp = |count: f64| count.clamp(0.0, MAX_PROGRAM_CHIPS as f64) as u32;
+    (clamp(from) + 1..=clamp(to)).collect()
+}
+
+pub struct ProgramChips;
+impl Catalogue for ProgramChips {
+    const KEY_PREFIX: &'static str = "split_on_program_chips_";
+
+    fn entries() -> Vec<(String, String)> {
+        (1..=MAX_PROGRAM_CHIPS)
+            .map(|count| {
+                (
+                    count.to_string(),
+                    format!("Split on reaching {} program chips", count),
+                )
+            })
+            .collect()
+    }
+}
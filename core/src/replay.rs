@@ -0,0 +1,132 @@
+use std::fmt;
+
+use crate::{
+    settings::SplitterSettings,
+    splitter::Splitter,
+    timer::{Logger, Timer, TimerState},
+    trace::{Frame, Playback},
+};
+
+/// Something the splitter did to the timer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Start,
+    Split,
+    Reset,
+    PauseGameTime,
+    ResumeGameTime,
+}
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Start => write!(f, "start"),
+            Self::Split => write!(f, "split"),
+            Self::Reset => write!(f, "reset"),
+            Self::PauseGameTime => write!(f, "pause game time"),
+            Self::ResumeGameTime => write!(f, "resume game time"),
+        }
+    }
+}
+
+/// What happened while replaying a trace, each with the tick it happened on.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub actions: Vec<(u64, Action)>,
+    /// everything the splitter logged
+    pub messages: Vec<(u64, String)>,
+}
+
+/// Stands in for the runtime's timer, keeping track of its state and writing down what the splitter does.
+/// Game time pausing is only reported when it changes, since the splitter asks for it every tick.
+struct ReplayTimer {
+    tick: u64,
+    state: TimerState,
+    game_time_paused: bool,
+    report: Report,
+}
+impl Default for ReplayTimer {
+    fn default() -> Self {
+        Self {
+            tick: 0,
+            state: TimerState::NotRunning,
+            game_time_paused: false,
+            report: Report::default(),
+        }
+    }
+}
+impl ReplayTimer {
+    fn act(&mut self, action: Action) {
+        self.report.actions.push((self.tick, action));
+    }
+}
+impl Timer for ReplayTimer {
+    fn state(&self) -> TimerState {
+        self.state
+    }
+
+    fn start(&mut self) {
+        if self.state() == TimerState::NotRunning {
+            self.state = TimerState::Running;
+            self.act(Action::Start);
+        }
+    }
+
+    fn split(&mut self) {
+        if self.state() == TimerState::Running {
+            self.act(Action::Split);
+        }
+    }
+
+    fn reset(&mut self) {
+        if self.state() != TimerState::NotRunning {
+            self.state = TimerState::NotRunning;
+            self.game_time_paused = false;
+            self.act(Action::Reset);
+        }
+    }
+
+    fn pause_game_time(&mut self) {
+        if !self.game_time_paused {
+            self.game_time_paused = true;
+            self.act(Action::PauseGameTime);
+        }
+    }
+
+    fn resume_game_time(&mut self) {
+        if self.game_time_paused {
+            self.game_time_paused = false;
+            self.act(Action::ResumeGameTime);
+        }
+    }
+}
+impl Logger for ReplayTimer {
+    fn print_message(&mut self, message: &str) {
+        self.report.messages.push((self.tick, message.to_owned()));
+    }
+}
+
+/// Runs a trace through the splitter with these settings.
+/// The trace only has the timer state where something other than the splitter changed it, like the runner
+/// resetting by hand, and the replay follows those. Everything else is up to the replayed splitter, so
+/// different settings can split differently than the recording did.
+pub fn replay(frames: &[Frame], settings: SplitterSettings) -> Report {
+    let mut timer = ReplayTimer::default();
+    let mut splitter = Splitter::new(settings, &mut timer);
+    let mut playback = Playback::default();
+
+    for frame in frames {
+        timer.tick = frame.tick;
+        // values from before recording stopped would look like they changed into the new ones
+        if frame.start {
+            playback = Playback::default();
+        }
+        if let Some(state) = frame.timer {
+            timer.state = state;
+        }
+
+        let vars = playback.update(&frame.snapshot);
+        splitter.update(&vars, &mut timer);
+    }
+
+    timer.report
+}
@@ -0,0 +1,154 @@
+use std::fmt;
+
+use crate::{
+    achievements::ACHIEVEMENT_COUNT, chapters::CHAPTERS, events::GameEvent, maps::MapTransition,
+    percent, settings::SplitterSettings, timer::Logger,
+};
+
+const CHAPTER_COUNT: usize = CHAPTERS.len();
+
+pub enum SplitCondition {
+    BossKilled(String),
+    Map(MapTransition),
+    /// index into `CHAPTERS`
+    ChapterReached(usize),
+    ProgramChips(f64),
+    /// index into the combined token recognition arrays
+    Achievement(usize),
+    Percent(f64),
+}
+impl SplitCondition {
+    fn parse(line: &str) -> Result<Self, String> {
+        let (kind, value) = line
+            .split_once(':')
+            .ok_or_else(|| format!("`{}` isn't in the form `kind:value`", line))?;
+        let value = value.trim();
+        let number = || {
+            value
+                .parse::<u32>()
+                .map_err(|_| format!("`{}` isn't a number in `{}`", value, line))
+        };
+
+        match kind.trim() {
+            "boss" => Ok(Self::BossKilled(value.to_owned())),
+            "map" => MapTransition::parse(value).map(Self::Map),
+            "chapter" => match number()? as usize {
+                1 => {
+                    Err("Chapter 1 starts along with the timer, so it can't be split on".to_owned())
+                }
+                chapter @ 2..=CHAPTER_COUNT => Ok(Self::ChapterReached(chapter - 1)),
+                _ => Err(format!("There's no chapter {}", value)),
+            },
+            "chips" => Ok(Self::ProgramChips(number()? as f64)),
+            "achievement" => match number()? as usize {
+                number @ 1..=ACHIEVEMENT_COUNT => Ok(Self::Achievement(number - 1)),
+                _ => Err(format!("There's no achievement {}", value)),
+            },
+            "percent" => percent::parse(value).map(Self::Percent),
+            kind => Err(format!("Unknown split kind `{}`", kind)),
+        }
+    }
+
+    /// Bosses in the boss gallery only count with `split_on_boss_gallery` on, like everywhere else.
+    pub fn check(&self, event: &GameEvent, settings: &SplitterSettings) -> bool {
+        match (self, event) {
+            (
+                Self::BossKilled(name),
+                &GameEvent::BossDefeated {
+                    name: defeated,
+                    in_gallery,
+                },
+            ) => name == defeated && (settings.split_on_boss_gallery || !in_gallery),
+            (Self::Map(transition), &GameEvent::MapEntered { from, to }) => {
+                transition.check(from, to)
+            }
+            (Self::ChapterReached(index), GameEvent::ChapterReached(reached)) => index == reached,
+            (Self::ProgramChips(count), &GameEvent::ChipCollected { from, to }) => {
+                from < *count && to >= *count
+            }
+            (Self::Achievement(index), GameEvent::AchievementUnlocked(unlocked)) => {
+                index == unlocked
+            }
+            (Self::Percent(percent), &GameEvent::PercentChanged { from, to }) => {
+                percent::crossed(from, to, *percent)
+            }
+            _ => false,
+        }
+    }
+}
+impl fmt::Display for SplitCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BossKilled(name) => write!(f, "boss:{}", name),
+            Self::Map(transition) => write!(f, "map:{}", transition),
+            Self::ChapterReached(index) => {
+                write!(f, "chapter:{} ({})", index + 1, CHAPTERS[*index].name)
+            }
+            Self::ProgramChips(count) => write!(f, "chips:{}", count),
+            Self::Achievement(index) => write!(f, "achievement:{}", index + 1),
+            Self::Percent(percent) => write!(f, "percent:{}", percent),
+        }
+    }
+}
+
+/// An ordered list of splits where only the next one can fire, read from the `route` setting
+/// with one split per line in the same order as the splits file.
+///
+/// Each line is one of:
+/// - `boss:<name>` when the boss with that `currentBossName` dies
+/// - `map:[from>]<to>[!excluded,...]` when going from one map to another, or from any map if `from` is left out.
+///   Maps are ids or the names listed in `MAPS`, and coming from an excluded map doesn't count.
+/// - `chapter:<n>` when chapter n starts (2-21, see `CHAPTERS`), chapter 1 starts with the timer
+/// - `chips:<n>` when reaching n program chips
+/// - `achievement:<n>` when achievement n is earned (1-100)
+/// - `percent:<n>` when reaching n% items, which can have decimals like `33.3`
+///
+/// Empty lines and lines starting with `#` are ignored.
+#[derive(Default)]
+pub struct Route {
+    splits: Vec<SplitCondition>,
+    next: usize,
+}
+impl Route {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let splits = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(SplitCondition::parse)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { splits, next: 0 })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.splits.is_empty()
+    }
+
+    /// Goes back to waiting on the first split.
+    pub fn reset(&mut self) {
+        self.next = 0;
+    }
+
+    /// Checks the next split in the route, moving on to the one after it if it fired.
+    pub fn check(
+        &mut self,
+        events: &[GameEvent],
+        settings: &SplitterSettings,
+        log: &mut impl Logger,
+    ) -> Option<&SplitCondition> {
+        let split = self.splits.get(self.next)?;
+        if !events.iter().any(|event| split.check(event, settings)) {
+            return None;
+        }
+
+        log.print_message(&format!(
+            "Route split {}/{} reached: {}",
+            self.next + 1,
+            self.splits.len(),
+            split
+        ));
+        self.next += 1;
+        Some(split)
+    }
+}
@@ -0,0 +1,365 @@
+use std::{
+    any::TypeId,
+    collections::{BTreeMap, HashMap},
+    marker::PhantomData,
+    sync::{Mutex, PoisonError},
+};
+
+use crate::{
+    achievements::Achievements, chapters::Chapters, maps::MapTransitions,
+    program_chips::ProgramChips,
+};
+
+/// Where settings are registered and read back from, the autosplitting runtime when running for real.
+pub trait SettingsStore {
+    /// Shows the setting to the runner as a checkbox.
+    fn add_bool(&mut self, key: &str, description: &str, default: bool);
+    /// The value the runner picked, if there is one.
+    fn get_bool(&self, key: &str) -> Option<bool>;
+    /// Tells the runner about a setting that holds text, like a route.
+    fn add_text(&mut self, key: &str, description: &str, default: &str);
+    /// The text the runner put in, if there is any.
+    fn get_text(&self, key: &str) -> Option<String>;
+}
+
+/// Settings picked by hand, like when replaying a trace, written out the way they'd be typed.
+/// Anything missing gets its default.
+impl SettingsStore for HashMap<String, String> {
+    fn add_bool(&mut self, key: &str, _description: &str, default: bool) {
+        self.entry(key.to_owned())
+            .or_insert_with(|| default.to_string());
+    }
+
+    fn get_bool(&self, key: &str) -> Option<bool> {
+        self.get(key)?.parse().ok()
+    }
+
+    fn add_text(&mut self, key: &str, _description: &str, default: &str) {
+        self.entry(key.to_owned())
+            .or_insert_with(|| default.to_owned());
+    }
+
+    fn get_text(&self, key: &str) -> Option<String> {
+        self.get(key).cloned()
+    }
+}
+
+/// Declares every boolean setting once so the struct, its defaults and the runtime
+/// registration can't drift apart. The doc comment of each field doubles as the
+/// description shown to the runner.
+/// After the first `;` come settings holding text, and after the second groups of settings
+/// generated from a catalogue, like one per chapter.
+macro_rules! splitter_settings {
+    (
+        $(
+            $(#[doc = $doc:literal])*
+            $field:ident = $default:expr,
+        )*
+        ;
+        $(
+            $(#[doc = $text_doc:literal])*
+            $text:ident = $text_default:literal,
+        )*
+        ;
+        $(
+            $(#[doc = $group_doc:literal])*
+            $group:ident: $group_ty:ty,
+        )*
+    ) => {
+        #[derive(Clone, PartialEq)]
+        pub struct SplitterSettings {
+            $(
+                $(#[doc = $doc])*
+                pub $field: bool,
+            )*
+            $(
+                $(#[doc = $text_doc])*
+                pub $text: String,
+            )*
+            $(
+                $(#[doc = $group_doc])*
+                pub $group: $group_ty,
+            )*
+        }
+
+        impl Default for SplitterSettings {
+            fn default() -> Self {
+                Self {
+                    $($field: $default,)*
+                    $($text: $text_default.to_owned(),)*
+                    $($group: Default::default(),)*
+                }
+            }
+        }
+
+        impl SplitterSettings {
+            /// Registers every setting with the runtime so it shows up in the settings GUI.
+            /// This must only be called once, as registering a key twice duplicates it.
+            pub fn register(store: &mut impl SettingsStore) {
+                $(
+                    store.add_bool(
+                        stringify!($field),
+                        &description(&[$($doc),*]),
+                        $default,
+                    );
+                )*
+                $(
+                    store.add_text(
+                        stringify!($text),
+                        &description(&[$($text_doc),*]),
+                        $text_default,
+                    );
+                )*
+                $(<$group_ty>::register(store);)*
+            }
+
+            /// Reads the values the runner picked, falling back to the default for
+            /// anything the runtime doesn't know about yet.
+            pub fn load(store: &impl SettingsStore) -> Self {
+                Self {
+                    $(
+                        $field: store
+                            .get_bool(stringify!($field))
+                            .unwrap_or($default),
+                    )*
+                    $(
+                        $text: store
+                            .get_text(stringify!($text))
+                            .unwrap_or_else(|| $text_default.to_owned()),
+                    )*
+                    $($group: <$group_ty>::load(store),)*
+                }
+            }
+
+            /// Lists the keys of every setting that differs between the two.
+            pub fn changed_keys(&self, other: &Self) -> Vec<String> {
+                let mut keys = Vec::new();
+                $(
+                    if self.$field != other.$field {
+                        keys.push(stringify!($field).to_owned());
+                    }
+                )*
+                $(
+                    if self.$text != other.$text {
+                        keys.push(stringify!($text).to_owned());
+                    }
+                )*
+                $(keys.extend(self.$group.changed_keys(&other.$group));)*
+                keys
+            }
+        }
+    };
+}
+
+/// Something there's one split setting per entry of, like chapters.
+pub trait Catalogue {
+    /// put in front of every entry's key so they don't collide with other settings
+    const KEY_PREFIX: &'static str;
+
+    /// The key and description of every entry, in order.
+    fn entries() -> Vec<(String, String)>;
+}
+
+/// One setting per entry of a catalogue, all off by default.
+pub struct Toggles<C> {
+    enabled: Vec<bool>,
+    catalogue: PhantomData<C>,
+}
+impl<C: Catalogue + 'static> Toggles<C> {
+    fn key(entry: &str) -> String {
+        format!("{}{}", C::KEY_PREFIX, entry)
+    }
+
+    /// The full key of every entry, worked out the first time they're needed since settings are read every tick.
+    fn keys() -> &'static [String] {
+        // a static in a generic function is shared by every catalogue, so they're told apart by type
+        static KEYS: Mutex<BTreeMap<TypeId, &'static [String]>> = Mutex::new(BTreeMap::new());
+
+        let mut keys = KEYS.lock().unwrap_or_else(PoisonError::into_inner);
+        keys.entry(TypeId::of::<C>()).or_insert_with(|| {
+            C::entries()
+                .into_iter()
+                .map(|(key, _)| Self::key(&key))
+                .collect::<Vec<_>>()
+                .leak()
+        })
+    }
+
+    pub fn register(store: &mut impl SettingsStore) {
+        for (key, description) in C::entries() {
+            store.add_bool(&Self::key(&key), &description, false);
+        }
+    }
+
+    pub fn load(store: &impl SettingsStore) -> Self {
+        let enabled = Self::keys()
+            .iter()
+            .map(|key| store.get_bool(key).unwrap_or(false))
+            .collect();
+        Self {
+            enabled,
+            catalogue: PhantomData,
+        }
+    }
+
+    pub fn changed_keys(&self, other: &Self) -> Vec<String> {
+        Self::keys()
+            .iter()
+            .zip(self.enabled.iter().zip(&other.enabled))
+            .filter(|(_, (a, b))| a != b)
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    /// Whether the entry at this index in the catalogue is turned on.
+    pub fn is_enabled(&self, index: usize) -> bool {
+        self.enabled.get(index).copied().unwrap_or(false)
+    }
+}
+// written out by hand since deriving would require the catalogue itself to implement these
+impl<C: Catalogue + 'static> Default for Toggles<C> {
+    /// Every entry off, the same as loading from a store that has none of them.
+    fn default() -> Self {
+        Self {
+            enabled: vec![false; Self::keys().len()],
+            catalogue: PhantomData,
+        }
+    }
+}
+impl<C> Clone for Toggles<C> {
+    fn clone(&self) -> Self {
+        Self {
+            enabled: self.enabled.clone(),
+            catalogue: PhantomData,
+        }
+    }
+}
+impl<C> PartialEq for Toggles<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.enabled == other.enabled
+    }
+}
+
+impl SplitterSettings {
+    /// How often the splitter should poll the game, which runs at 30fps unless the double speed cheat is on.
+    pub fn tick_rate(&self) -> f64 {
+        if self.cheat_double_speed {
+            60.0
+        } else {
+            30.0
+        }
+    }
+}
+
+/// Joins the lines of a doc comment back together without the leading space rustdoc keeps.
+fn description(lines: &[&str]) -> String {
+    lines
+        .iter()
+        .map(|line| line.trim())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+splitter_settings! {
+    /// Split whenever you start a boss fight
+    /// In case you want to separate doing normal chapter movement from doing a boss fight
+    /// Only the first attempt at each boss splits, retries don't.
+    split_on_every_boss_start = true,
+    /// Split after every boss
+    /// Internally checks if the currentBossName value changes from "has something" to "doesn't have something".
+    /// I don't think anything else sets this value so it should be fine.
+    split_on_every_boss = true,
+    /// Split after every chapter starts
+    /// There isn't a good way to do detect this.
+    /// It uses a manually defined map of gameProgress values
+    split_on_every_chapter = false,
+    /// Split whenever a Program Chip is obtained
+    /// Only counts chips we didn't already have this run, so loading an older save doesn't split again.
+    /// I'm not sure if this is useful since it's always obtained after a boss.
+    /// maybe it can be used instead of splitting on bosses.
+    split_on_every_program_chip = false,
+    /// Split when the gameCompleted value changes to true
+    /// This gets set immediately before returning to the title after the epilogue.
+    /// May or may not be useful.
+    split_on_game_completed = false,
+    /// Split every time an achievement is gotten
+    /// Is probably useful if you're running an AllAchievements% category?
+    split_on_achievement = false,
+    /// Split every time an boss in the gallery is beaten
+    /// Is probably useful if you're running an a bossgallery% category?
+    split_on_boss_gallery = false,
+    /// Split every 10% of items found
+    /// Each step splits once per run, even if a single pickup skips past it.
+    split_every_ten_percent = false,
+    /// Split at 100% items
+    split_at_100_percent = true,
+    /// Split whenever the map ID changes
+    /// Not really recommended to turn this on.
+    split_on_every_map_change = false,
+    /// Split whenever the map ID changes to a new map
+    /// Stores an internal reference to map IDs and splits whenever it encounters a new map
+    split_on_every_new_map_change = false,
+    /// Split following the route in the `route` setting
+    /// Ignores every other split setting, and only the next split in the route can fire.
+    split_on_route = false,
+    /// Split separately for everything that happened at the same time
+    /// Like a boss dying on the same tick as a chapter starting. The extra splits happen on the following ticks
+    /// in priority order, otherwise they're combined into one split. Settings firing for the same thing always split once.
+    queue_simultaneous_splits = false,
+
+    /// Pause timer in a cutscene
+    /// Unfortunateley I don't think this value is ever actually used internally in the game for anything so this does nothing.
+    remove_cutscene_time = false,
+    /// Pause timer when the game is paused
+    /// Sometimes the game pauses to do cutscenes, but this also handles the pause menu.
+    remove_pause_time = false,
+    /// Pause Timer in the gameover screen
+    /// This setting exists so that you're not forced to play on quick gameover mode.
+    remove_gameover = true,
+
+    /// Start the timer when you continue the game
+    /// I'm not sure if this is actually useful or not but maybe someone wants it.
+    start_on_continue = false,
+    /// Start on map change
+    /// This is useful for ILs i guess, since most of them start on portal entry. Doesn't count the title screen.
+    start_on_map_change = false,
+
+    /// Reset timer when you go back to the title screen
+    /// I'm not sure if this is actually useful or not but maybe someone wants it.
+    reset_on_titlescreen = false,
+
+    /// Enable when using the double speed cheat
+    /// There might be a way to detect this through pointers but it's annoying
+    cheat_double_speed = false,
+    /// Poll faster than the game runs during boss fights and the final chapter
+    /// Some values only change for a single frame, like a boss dying or the game being completed, this makes sure we don't miss them.
+    poll_faster_near_events = true,
+    /// Log a trace of the game's variables every tick
+    /// Turn this on and send us the log if the splitter did something wrong, so we can replay your run.
+    record_trace = false,
+    ;
+    /// Split when one of these bosses dies, one `currentBossName` per line
+    /// The splitter logs the name of every boss it sees die, so names can be copied from a run's log.
+    split_on_bosses = "",
+    /// The route for `split_on_route`, one split per line in the same order as your splits file
+    /// Each line is `boss:<name>`, `map:[from>]<to>[!excluded,...]`, `chapter:<n>`, `chips:<n>`, `achievement:<n>` or `percent:<n>`.
+    route = "",
+    /// More map transitions to split on, one `[once ][from>]to[!excluded,...]` per line
+    /// Maps are ids or names, and coming from an excluded map doesn't count. Starting a line with `once` only splits the first time in a run.
+    custom_map_transitions = "",
+    /// Split the first time each of these item percentages is reached, separated by commas or lines
+    /// Any percentage above 0 and up to 100 works, like `33.3, 50, 66.6`.
+    split_on_percent = "",
+    /// Which split settings win when several fire for the same thing, one setting key per line, highest first
+    /// Settings that aren't listed keep their usual order after the listed ones, see `SplitReason`.
+    split_priority = "",
+    ;
+    /// Split when one of these achievements is earned
+    split_on_achievements: Toggles<Achievements>,
+    /// Split when one of these chapters starts
+    split_on_chapters: Toggles<Chapters>,
+    /// Split when moving between these maps, see `MAP_TRANSITIONS`
+    map_transitions: Toggles<MapTransitions>,
+    /// Split the first time each of these program chip counts is reached
+    split_on_program_chips: Toggles<ProgramChips>,
+}
@@ -0,0 +1,600 @@
+use std::{collections::VecDeque, fmt};
+
+use crate::{
+    achievements, bosses, chapters,
+    events::{self, GameEvent},
+    ledger::{Milestone, RunLedger},
+    maps::{self, MapTransition, MAP_ID_INTRO_SCENE, MAP_ID_TITLE_SCREEN},
+    percent, program_chips,
+    route::Route,
+    settings::SplitterSettings,
+    timer::{Logger, Timer, TimerState},
+    variables::Variables,
+};
+
+/// The split setting that asked for a split.
+/// Declared in the order they win in unless the `split_priority` setting says otherwise, see `SplitPriority`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitReason {
+    EveryBossStart,
+    EveryBoss,
+    Bosses,
+    EveryProgramChip,
+    ProgramChips,
+    Achievement,
+    Achievements,
+    EveryChapter,
+    Chapters,
+    MapTransitions,
+    Percent,
+    EveryTenPercent,
+    HundredPercent,
+    GameCompleted,
+    EveryNewMapChange,
+    EveryMapChange,
+}
+impl SplitReason {
+    pub const ALL: [Self; 16] = [
+        Self::EveryBossStart,
+        Self::EveryBoss,
+        Self::Bosses,
+        Self::EveryProgramChip,
+        Self::ProgramChips,
+        Self::Achievement,
+        Self::Achievements,
+        Self::EveryChapter,
+        Self::Chapters,
+        Self::MapTransitions,
+        Self::Percent,
+        Self::EveryTenPercent,
+        Self::HundredPercent,
+        Self::GameCompleted,
+        Self::EveryNewMapChange,
+        Self::EveryMapChange,
+    ];
+
+    /// The key of the setting, as the runner sees it.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::EveryBossStart => "split_on_every_boss_start",
+            Self::EveryBoss => "split_on_every_boss",
+            Self::Bosses => "split_on_bosses",
+            Self::EveryProgramChip => "split_on_every_program_chip",
+            Self::ProgramChips => "split_on_program_chips",
+            Self::Achievement => "split_on_achievement",
+            Self::Achievements => "split_on_achievements",
+            Self::EveryChapter => "split_on_every_chapter",
+            Self::Chapters => "split_on_chapters",
+            Self::MapTransitions => "split_on_map_transitions",
+            Self::Percent => "split_on_percent",
+            Self::EveryTenPercent => "split_every_ten_percent",
+            Self::HundredPercent => "split_at_100_percent",
+            Self::GameCompleted => "split_on_game_completed",
+            Self::EveryNewMapChange => "split_on_every_new_map_change",
+            Self::EveryMapChange => "split_on_every_map_change",
+        }
+    }
+}
+impl fmt::Display for SplitReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.key())
+    }
+}
+
+/// Which split setting wins when several fire for the same thing, like a boss that's both
+/// listed in `split_on_bosses` and caught by `split_on_every_boss`, read from the `split_priority` setting.
+/// With `queue_simultaneous_splits` on, this is also the order the queued splits happen in.
+pub struct SplitPriority {
+    /// every reason, highest priority first
+    order: Vec<SplitReason>,
+}
+impl SplitPriority {
+    /// Reads one setting key per line, highest priority first.
+    /// Settings that aren't listed keep their usual order after the ones that are.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut order = Vec::new();
+        for line in text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        {
+            let reason = SplitReason::ALL
+                .into_iter()
+                .find(|reason| reason.key() == line)
+                .ok_or_else(|| format!("Unknown split setting `{}`", line))?;
+            if !order.contains(&reason) {
+                order.push(reason);
+            }
+        }
+        for reason in SplitReason::ALL {
+            if !order.contains(&reason) {
+                order.push(reason);
+            }
+        }
+
+        Ok(Self { order })
+    }
+
+    /// Where the reason comes in the priority order, lower wins.
+    pub fn rank(&self, reason: SplitReason) -> usize {
+        self.order
+            .iter()
+            .position(|&listed| listed == reason)
+            .unwrap_or(self.order.len())
+    }
+
+    /// The reason that wins out of several that fired for the same thing.
+    pub fn best(&self, reasons: &[SplitReason]) -> Option<SplitReason> {
+        reasons
+            .iter()
+            .copied()
+            .min_by_key(|&reason| self.rank(reason))
+    }
+}
+impl Default for SplitPriority {
+    fn default() -> Self {
+        Self {
+            order: SplitReason::ALL.to_vec(),
+        }
+    }
+}
+
+/// The splits that fired, in the order they should happen in.
+struct SplitQueue {
+    /// parsed from `settings.split_priority`
+    priority: SplitPriority,
+    /// splits that fired on the same tick as another, waiting for their own tick
+    pending: VecDeque<SplitReason>,
+}
+
+/// The split settings written as text, read again whenever they change.
+struct TextSplits {
+    /// parsed from `settings.custom_map_transitions`
+    map_transitions: Vec<MapTransition>,
+    /// parsed from `settings.split_on_percent`
+    percentages: Vec<f64>,
+}
+
+/// Decides when to start, split, reset and pause the timer from each tick's variables.
+/// Doesn't know about the game process or the runtime, so it runs anywhere.
+pub struct Splitter {
+    settings: SplitterSettings,
+    /// parsed from `settings.route`
+    route: Route,
+    text_splits: TextSplits,
+    /// what already split this run
+    ledger: RunLedger,
+    splits: SplitQueue,
+}
+impl Splitter {
+    pub fn new(settings: SplitterSettings, log: &mut impl Logger) -> Self {
+        Self {
+            route: load_route(&settings, log),
+            text_splits: TextSplits {
+                map_transitions: load_map_transitions(&settings, log),
+                percentages: load_percentages(&settings, log),
+            },
+            splits: SplitQueue {
+                priority: load_priority(&settings, log),
+                pending: VecDeque::new(),
+            },
+            settings,
+            ledger: RunLedger::default(),
+        }
+    }
+
+    pub fn settings(&self) -> &SplitterSettings {
+        &self.settings
+    }
+
+    /// Switches to settings the runner changed, reading the text ones again if they're among them.
+    pub fn set_settings(&mut self, settings: SplitterSettings, log: &mut impl Logger) {
+        if settings.route != self.settings.route
+            || settings.split_on_route != self.settings.split_on_route
+        {
+            self.route = load_route(&settings, log);
+        }
+        if settings.custom_map_transitions != self.settings.custom_map_transitions {
+            self.text_splits.map_transitions = load_map_transitions(&settings, log);
+        }
+        if settings.split_on_percent != self.settings.split_on_percent {
+            self.text_splits.percentages = load_percentages(&settings, log);
+        }
+        if settings.split_priority != self.settings.split_priority {
+            self.splits.priority = load_priority(&settings, log);
+        }
+        self.settings = settings;
+    }
+
+    pub fn update(&mut self, vars: &Variables, host: &mut (impl Timer + Logger)) {
+        let events = events::collect(vars);
+
+        handle_resets(&events, &self.settings, host);
+        handle_is_loading(vars, &self.settings, host);
+
+        match host.state() {
+            TimerState::NotRunning => timer_not_running(
+                &events,
+                &self.settings,
+                &mut self.route,
+                &mut self.ledger,
+                &mut self.splits,
+                host,
+            ),
+            TimerState::Running => timer_running(
+                &events,
+                &self.settings,
+                &mut self.route,
+                &self.text_splits,
+                &mut self.ledger,
+                &mut self.splits,
+                host,
+            ),
+            TimerState::Paused => {}
+            TimerState::Ended => {}
+        }
+    }
+}
+
+/// Reads the `route` setting, warning when `split_on_route` can't do anything with it.
+fn load_route(settings: &SplitterSettings, log: &mut impl Logger) -> Route {
+    let route = Route::parse(&settings.route).unwrap_or_else(|err| {
+        log.print_message(&format!("Couldn't read the route: {}", err));
+        Route::default()
+    });
+    if settings.split_on_route && route.is_empty() {
+        log.print_message(
+            "`split_on_route` is on but there's no route, so the other split settings are used instead",
+        );
+    }
+    route
+}
+
+/// Reads the `custom_map_transitions` setting, splitting on none of them if any line is wrong.
+fn load_map_transitions(settings: &SplitterSettings, log: &mut impl Logger) -> Vec<MapTransition> {
+    maps::parse_transitions(&settings.custom_map_transitions).unwrap_or_else(|err| {
+        log.print_message(&format!("Couldn't read `custom_map_transitions`: {}", err));
+        Vec::new()
+    })
+}
+
+/// Reads the `split_on_percent` setting, splitting on none of them if any is wrong.
+fn load_percentages(settings: &SplitterSettings, log: &mut impl Logger) -> Vec<f64> {
+    percent::parse_list(&settings.split_on_percent).unwrap_or_else(|err| {
+        log.print_message(&format!("Couldn't read `split_on_percent`: {}", err));
+        Vec::new()
+    })
+}
+
+/// Reads the `split_priority` setting, keeping the usual order if any line is wrong.
+fn load_priority(settings: &SplitterSettings, log: &mut impl Logger) -> SplitPriority {
+    SplitPriority::parse(&settings.split_priority).unwrap_or_else(|err| {
+        log.print_message(&format!("Couldn't read `split_priority`: {}", err));
+        SplitPriority::default()
+    })
+}
+
+fn handle_is_loading(vars: &Variables, settings: &SplitterSettings, timer: &mut impl Timer) {
+    if (settings.remove_pause_time && vars.is_paused.is_some_and(|x| x.current == 1.0))
+        || (settings.remove_cutscene_time && vars.in_cutscene.is_some_and(|x| x.current == 1.0))
+        || (settings.remove_gameover && vars.in_game_over.is_some_and(|x| x.current == 1.0))
+    {
+        timer.pause_game_time();
+    } else {
+        timer.resume_game_time();
+    }
+}
+
+fn handle_resets(events: &[GameEvent], settings: &SplitterSettings, timer: &mut impl Timer) {
+    for event in events {
+        match *event {
+            // reset when we reach the title screen
+            GameEvent::MapEntered {
+                to: MAP_ID_TITLE_SCREEN,
+                ..
+            } if settings.reset_on_titlescreen => {
+                timer.reset();
+                return;
+            }
+            // reset when we click on the new game button on the main menu
+            GameEvent::NewGameStarted => {
+                timer.reset();
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn timer_not_running(
+    events: &[GameEvent],
+    settings: &SplitterSettings,
+    route: &mut Route,
+    ledger: &mut RunLedger,
+    splits: &mut SplitQueue,
+    host: &mut (impl Timer + Logger),
+) {
+    ledger.clear();
+    splits.pending.clear();
+    route.reset();
+
+    let start_map = if events.contains(&GameEvent::NewGameStarted) {
+        host.print_message(
+            "Starting timer since we've found the intro scene and our game progress is 0",
+        );
+        Some(MAP_ID_INTRO_SCENE)
+    } else {
+        events.iter().find_map(|event| {
+            let &GameEvent::MapEntered { from, to } = event else {
+                return None;
+            };
+
+            // this setting could be useful for categories like "bossgallery%" i guess
+            if settings.start_on_continue && from == MAP_ID_TITLE_SCREEN {
+                host.print_message(&format!(
+                    "Starting timer due to `start_on_continue` into {}",
+                    maps::name(to)
+                ));
+                Some(to)
+            }
+            // useful for ILs probably; ignores the title screen
+            else if settings.start_on_map_change
+                && from != MAP_ID_TITLE_SCREEN
+                && to != MAP_ID_TITLE_SCREEN
+            {
+                host.print_message(&format!(
+                    "Starting timer due to `start_on_map_change` into {}",
+                    maps::name(to)
+                ));
+                Some(to)
+            } else {
+                None
+            }
+        })
+    };
+
+    if let Some(map_id) = start_map {
+        ledger.first(Milestone::Map(map_id));
+        host.start();
+    }
+}
+
+fn timer_running(
+    events: &[GameEvent],
+    settings: &SplitterSettings,
+    route: &mut Route,
+    text_splits: &TextSplits,
+    ledger: &mut RunLedger,
+    splits: &mut SplitQueue,
+    host: &mut (impl Timer + Logger),
+) {
+    // the route replaces every other split setting, and only arms its next split
+    if settings.split_on_route && !route.is_empty() {
+        if route.check(events, settings, host).is_some() {
+            host.split();
+        }
+        return;
+    }
+
+    // only the setting that wins splits for each thing, so a boss caught by two settings is still one split
+    let mut fired = events
+        .iter()
+        .flat_map(|event| fired_splits(event, settings, text_splits, ledger, host))
+        .filter_map(|reasons| splits.priority.best(&reasons))
+        .collect::<Vec<_>>();
+    fired.sort_by_key(|&reason| splits.priority.rank(reason));
+
+    // a setting shows up more than once when it fired for several things at once, like jumping past two chapters
+    if settings.queue_simultaneous_splits {
+        splits.pending.extend(fired);
+        if let Some(reason) = splits.pending.pop_front() {
+            host.print_message(&format!(
+                "Splitting due to `{}` ({} more queued)",
+                reason,
+                splits.pending.len()
+            ));
+            host.split();
+        }
+    } else if !fired.is_empty() {
+        fired.dedup();
+        let reasons = fired.iter().map(|reason| reason.key()).collect::<Vec<_>>();
+        host.print_message(&format!("Splitting due to `{}`", reasons.join("`, `")));
+        host.split();
+    }
+}
+
+/// Collects every split setting the event should split for, grouped by the thing they fired for,
+/// like each program chip count reached, so only one of them splits for it.
+fn fired_splits(
+    event: &GameEvent,
+    settings: &SplitterSettings,
+    text_splits: &TextSplits,
+    ledger: &mut RunLedger,
+    log: &mut impl Logger,
+) -> Vec<Vec<SplitReason>> {
+    let mut fired = Vec::new();
+    // what fired for the event itself, anything that can fire for several things at once goes straight in `fired`
+    let mut reasons = Vec::new();
+
+    match *event {
+        GameEvent::MapEntered { from, to } => {
+            if settings.split_on_every_new_map_change && ledger.first(Milestone::Map(to)) {
+                log.print_message(&format!(
+                    "Found a new map while using `split_on_every_new_map_change`: {}",
+                    maps::name(to)
+                ));
+
+                if from != MAP_ID_TITLE_SCREEN && to != MAP_ID_TITLE_SCREEN {
+                    reasons.push(SplitReason::EveryNewMapChange);
+                }
+            }
+            // Check the map transitions picked in the settings, like entering the sacred temple
+            for (index, setting) in maps::MAP_TRANSITIONS.iter().enumerate() {
+                if settings.map_transitions.is_enabled(index)
+                    && setting.transition.check(from, to)
+                    && (!setting.transition.once || ledger.first(Milestone::MapTransition(index)))
+                {
+                    log.print_message(&format!(
+                        "Moved to {} for `{}`",
+                        setting.transition, setting.key
+                    ));
+                    reasons.push(SplitReason::MapTransitions);
+                }
+            }
+            for (index, transition) in text_splits.map_transitions.iter().enumerate() {
+                if transition.check(from, to)
+                    && (!transition.once || ledger.first(Milestone::CustomMapTransition(index)))
+                {
+                    log.print_message(&format!(
+                        "Moved to {} for `custom_map_transitions`",
+                        transition
+                    ));
+                    reasons.push(SplitReason::MapTransitions);
+                }
+            }
+            if settings.split_on_every_map_change {
+                log.print_message(&format!(
+                    "Went from {} to {}",
+                    maps::name(from),
+                    maps::name(to)
+                ));
+                reasons.push(SplitReason::EveryMapChange);
+            }
+        }
+        // The "game completed" value changes right before heading to the title in the epilogue
+        GameEvent::GameCompleted => {
+            if settings.split_on_game_completed {
+                reasons.push(SplitReason::GameCompleted);
+            }
+        }
+        // Each boss only counts once per run, and separately in the boss gallery.
+        GameEvent::BossDefeated { name, in_gallery } => {
+            if !ledger.first(Milestone::BossDefeated {
+                name: name.to_owned(),
+                in_gallery,
+            }) {
+                return fired;
+            }
+
+            if settings.split_on_every_boss {
+                // If we have the "split_on_boss_gallery" setting checked we always want to split on a boss death
+                // otherwise only split if we're not currently in the boss gallery
+                if settings.split_on_boss_gallery || !in_gallery {
+                    reasons.push(SplitReason::EveryBoss);
+                } else {
+                    log.print_message("split_on_every_boss: Completed a boss but it was in the boss_gallery, and we don't have that option enabled, so we're not splitting.");
+                }
+            }
+
+            // Check if the boss that just died is one of the ones picked in the settings
+            if !bosses::listed(&settings.split_on_bosses, name) {
+                log.print_message(&format!(
+                    "Defeated `{}`, which isn't listed in `split_on_bosses`",
+                    name
+                ));
+            } else if settings.split_on_boss_gallery || !in_gallery {
+                log.print_message(&format!("Defeated `{}`", name));
+                reasons.push(SplitReason::Bosses);
+            }
+        }
+        // Retrying a boss sets the name again, so only the first attempt counts.
+        GameEvent::BossStarted { name, in_gallery } => {
+            if settings.split_on_every_boss_start
+                && ledger.first(Milestone::BossStarted {
+                    name: name.to_owned(),
+                    in_gallery,
+                })
+            {
+                if settings.split_on_boss_gallery || !in_gallery {
+                    reasons.push(SplitReason::EveryBossStart);
+                } else {
+                    log.print_message("split_on_every_boss_start: Started a boss but it was in the boss_gallery, and we don't have that option enabled, so we're not splitting.");
+                }
+            }
+        }
+        GameEvent::AchievementUnlocked(index) => {
+            if !ledger.first(Milestone::Achievement(index)) {
+                return fired;
+            }
+            log.print_message(&format!(
+                "Unlocked achievement {} ({})",
+                index + 1,
+                achievements::name(index)
+            ));
+
+            if settings.split_on_achievement {
+                reasons.push(SplitReason::Achievement);
+            }
+            if settings.split_on_achievements.is_enabled(index) {
+                reasons.push(SplitReason::Achievements);
+            }
+        }
+        // only counts we haven't reached yet this run, so reloading an older save doesn't split again
+        GameEvent::ChipCollected { from, to } => {
+            // a garbage read mustn't raise the high-water mark, or the real counts below it would never split
+            if !program_chips::plausible(to) {
+                log.print_message(&format!(
+                    "Ignoring an impossible program chip count of {}",
+                    to
+                ));
+                return fired;
+            }
+            let from = if program_chips::plausible(from) {
+                from
+            } else {
+                to
+            };
+            let Some((from, to)) = ledger.chips.raise(from, to) else {
+                return fired;
+            };
+            for count in program_chips::reached(from, to) {
+                log.print_message(&format!("Reached {} program chips", count));
+
+                let mut reasons = Vec::new();
+                if settings.split_on_every_program_chip {
+                    reasons.push(SplitReason::EveryProgramChip);
+                }
+                if settings
+                    .split_on_program_chips
+                    .is_enabled(count as usize - 1)
+                {
+                    reasons.push(SplitReason::ProgramChips);
+                }
+                fired.push(reasons);
+            }
+        }
+        GameEvent::ChapterReached(index) => {
+            if !ledger.first(Milestone::Chapter(index)) {
+                return fired;
+            }
+            log.print_message(&format!(
+                "Entered chapter {}: {}",
+                index + 1,
+                chapters::CHAPTERS[index].name
+            ));
+
+            if settings.split_on_every_chapter {
+                reasons.push(SplitReason::EveryChapter);
+            }
+            // the chapter settings leave out the first chapter
+            if settings.split_on_chapters.is_enabled(index - 1) {
+                reasons.push(SplitReason::Chapters);
+            }
+        }
+        // every item percentage threshold passed for the first time, even when one pickup skips past several
+        GameEvent::PercentChanged { from, to } => {
+            let Some((from, to)) = ledger.percent.raise(from, to) else {
+                return fired;
+            };
+            let thresholds = percent::thresholds(settings, &text_splits.percentages);
+            for (threshold, reasons) in percent::reached(from, to, &thresholds) {
+                log.print_message(&format!("Reached {}% items", threshold));
+                fired.push(reasons);
+            }
+        }
+        GameEvent::NewGameStarted => {}
+    }
+    fired.push(reasons);
+
+    fired
+}
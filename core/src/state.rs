@@ -0,0 +1,167 @@
+use bytemuck::Pod;
+use std::fmt::Debug;
+
+use crate::{
+    memory::MemorySource,
+    pointers::PointerTable,
+    timer::Logger,
+    variables::Variables,
+    watcher::{Pair, Watcher},
+};
+
+pub struct Variable<T> {
+    var: Watcher<T>,
+    /// shown in the logs when the variable can't be read
+    name: &'static str,
+    base_address: u32,
+    address_path: Vec<u32>,
+    /// the tick this variable stopped being readable on, if it currently can't be read
+    unreadable_since: Option<u64>,
+}
+impl<T: Pod + Debug> Variable<T> {
+    pub fn new(name: &'static str, base_address: u32, address_path: Vec<u32>) -> Self {
+        Self {
+            var: Watcher::new(),
+            name,
+            base_address,
+            address_path,
+            unreadable_since: None,
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        memory: &impl MemorySource,
+        tick: u64,
+        log: &mut impl Logger,
+    ) -> Option<&Pair<T>> {
+        let value = memory
+            .read_pointer_path32(self.base_address, &self.address_path)
+            .ok();
+
+        match (&value, self.unreadable_since) {
+            (None, None) => {
+                log.print_message(&format!(
+                    "`{}` can't be read since tick {}, anything depending on it is disabled",
+                    self.name, tick
+                ));
+                self.unreadable_since = Some(tick);
+            }
+            (Some(_), Some(since)) => {
+                log.print_message(&format!(
+                    "`{}` can be read again after being unreadable since tick {}",
+                    self.name, since
+                ));
+                self.unreadable_since = None;
+            }
+            _ => {}
+        }
+
+        self.var.update(value)
+    }
+}
+
+pub struct State {
+    /// internal gamemaker room mapping
+    pub map_id: Variable<i32>,
+    /// arbitrary internal progress number to check for cutscenes/etc
+    pub game_progress: Variable<f64>,
+    /// how many program chips we have
+    pub program_chips: Variable<f64>,
+    /// what percent of the items are found/etc
+    pub game_percent: Variable<f64>,
+    /// if the game is done v handy
+    pub game_completed: Variable<f64>,
+
+    /// Achievements part 1.
+    /// Each entry in the list is either 0x31 for achieved or 0x30 for not
+    pub token_recognitions: Variable<[u8; 50]>,
+    /// Achievements part 2.
+    /// Each entry in the list is either 0x31 for achieved or 0x30 for not.
+    pub token_recognitions_two: Variable<[u8; 50]>,
+
+    /// a lot of things aren't counted as a cutscene apparently
+    pub in_cutscene: Variable<f64>,
+    /// if the game over screen is displayed
+    pub in_game_over: Variable<f64>,
+    /// if the pause menu is up, or the game is just generally paused like in a ""cutscene""
+    pub is_paused: Variable<f64>,
+    /// the name of the boss if one exists
+    pub current_boss: Variable<[u8; 100]>,
+    /// 0 = no boss, 1+ = fighting boss?
+    pub boss_track: Variable<f64>,
+    /// 0 = not in boss gallery, 1 = in boss gallery
+    pub boss_gallery: Variable<f64>,
+
+    /// how many times we've polled the game, so the logs can say when a variable broke
+    tick: u64,
+}
+impl State {
+    /// `module_base` is where the main module is loaded, and `globals` is the absolute address of the pointer to the globals instance.
+    /// Globals we can't locate get an empty path, which never reads successfully.
+    pub fn new(
+        pointers: &PointerTable,
+        module_base: u32,
+        globals: u32,
+        log: &mut impl Logger,
+    ) -> Self {
+        // plain values sit directly in the globals array, strings are a pointer to their characters
+        let mut global = |name| {
+            pointers
+                .global_offset(name, log)
+                .map_or_else(Vec::new, |offset| vec![0x0, pointers.globals_vars, offset])
+        };
+        let string = |mut path: Vec<u32>| {
+            if !path.is_empty() {
+                path.push(0x0);
+            }
+            path
+        };
+        Self {
+            map_id: Variable::new("map_id", module_base, vec![pointers.map_id]),
+            game_progress: Variable::new("game_progress", globals, global("gameProgress")),
+            program_chips: Variable::new("program_chips", globals, global("programChips")),
+            game_percent: Variable::new("game_percent", globals, global("gamePercent")),
+            game_completed: Variable::new("game_completed", globals, global("gameCompleted")),
+            token_recognitions: Variable::new(
+                "token_recognitions",
+                globals,
+                string(global("tokenRecognitions")),
+            ),
+            token_recognitions_two: Variable::new(
+                "token_recognitions_two",
+                globals,
+                string(global("tokenRecognitionsTwo")),
+            ),
+            in_cutscene: Variable::new("in_cutscene", globals, global("inCutscene")),
+            in_game_over: Variable::new("in_game_over", globals, global("inGameOver")),
+            is_paused: Variable::new("is_paused", globals, global("isPaused")),
+            current_boss: Variable::new("current_boss", globals, string(global("currentBossName"))),
+            boss_track: Variable::new("boss_track", globals, global("bossTrack")),
+            boss_gallery: Variable::new("boss_gallery", globals, global("bossGallery")),
+            tick: 0,
+        }
+    }
+
+    /// Reads every variable. Ones that can't be read are `None` so the rest keep working.
+    pub fn update(&mut self, memory: &impl MemorySource, log: &mut impl Logger) -> Variables<'_> {
+        self.tick += 1;
+        let tick = self.tick;
+
+        Variables {
+            map_id: self.map_id.update(memory, tick, log),
+            game_progress: self.game_progress.update(memory, tick, log),
+            program_chips: self.program_chips.update(memory, tick, log),
+            game_percent: self.game_percent.update(memory, tick, log),
+            game_completed: self.game_completed.update(memory, tick, log),
+            token_recognitions: self.token_recognitions.update(memory, tick, log),
+            token_recognitions_two: self.token_recognitions_two.update(memory, tick, log),
+            in_cutscene: self.in_cutscene.update(memory, tick, log),
+            in_game_over: self.in_game_over.update(memory, tick, log),
+            is_paused: self.is_paused.update(memory, tick, log),
+            current_boss: self.current_boss.update(memory, tick, log),
+            boss_track: self.boss_track.update(memory, tick, log),
+            boss_gallery: self.boss_gallery.update(memory, tick, log),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+/// What the timer is doing, as the runtime reports it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerState {
+    NotRunning,
+    Running,
+    Paused,
+    Ended,
+}
+
+/// The timer the splitter controls, the one in the autosplitting runtime when running for real.
+pub trait Timer {
+    fn state(&self) -> TimerState;
+    fn start(&mut self);
+    fn split(&mut self);
+    fn reset(&mut self);
+    fn pause_game_time(&mut self);
+    fn resume_game_time(&mut self);
+}
+
+/// Where the splitter explains what it's doing.
+pub trait Logger {
+    fn print_message(&mut self, message: &str);
+}
@@ -0,0 +1,266 @@
+use std::fmt::Write;
+
+use crate::{timer::TimerState, variables::Variables, watcher::Watcher};
+
+/// Starts every trace line, so traces can be picked out of a log with anything else in it.
+/// The number goes up whenever the format changes in a way older traces can't be read.
+pub const TRACE_MARKER: &str = "DT3TRACE1";
+
+/// A value that can be written into a trace and read back exactly.
+trait TraceValue: Sized {
+    fn encode(&self) -> String;
+    fn decode(text: &str) -> Option<Self>;
+}
+impl TraceValue for i32 {
+    fn encode(&self) -> String {
+        self.to_string()
+    }
+
+    fn decode(text: &str) -> Option<Self> {
+        text.parse().ok()
+    }
+}
+// rust prints the shortest text that parses back to the same double
+impl TraceValue for f64 {
+    fn encode(&self) -> String {
+        self.to_string()
+    }
+
+    fn decode(text: &str) -> Option<Self> {
+        text.parse().ok()
+    }
+}
+// strings and token arrays are written as hex, so spaces and odd bytes can't break the line
+impl<const N: usize> TraceValue for [u8; N] {
+    fn encode(&self) -> String {
+        self.iter()
+            .fold(String::with_capacity(N * 2), |mut hex, byte| {
+                let _ = write!(hex, "{:02x}", byte);
+                hex
+            })
+    }
+
+    fn decode(text: &str) -> Option<Self> {
+        if text.len() != N * 2 {
+            return None;
+        }
+        let mut bytes = [0; N];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(text.get(i * 2..i * 2 + 2)?, 16).ok()?;
+        }
+        Some(bytes)
+    }
+}
+
+/// `-` stands for a variable that couldn't be read.
+fn encode<T: TraceValue>(value: &Option<T>) -> String {
+    match value {
+        Some(value) => value.encode(),
+        None => "-".to_owned(),
+    }
+}
+
+fn decode<T: TraceValue>(field: &str, text: &str) -> Result<Option<T>, String> {
+    if text == "-" {
+        return Ok(None);
+    }
+    T::decode(text)
+        .map(Some)
+        .ok_or_else(|| format!("`{}` isn't a valid value for `{}`", text, field))
+}
+
+/// Lists every variable once so recording and playing back can't drift apart from each other.
+/// Every field of `Variables` has to be here, and is written to the trace under its own name.
+macro_rules! trace_fields {
+    ($($field:ident: $ty:ty,)*) => {
+        /// The value of every variable on one tick, `None` where it couldn't be read.
+        #[derive(Debug, Clone, Default, PartialEq)]
+        pub struct Snapshot {
+            $(pub $field: Option<$ty>,)*
+        }
+        impl Snapshot {
+            pub fn new(vars: &Variables) -> Self {
+                Self {
+                    $($field: vars.$field.map(|x| x.current),)*
+                }
+            }
+
+            /// `field=value` for every field that differs from `previous`, or for every field if there's nothing before it.
+            fn changes(&self, previous: Option<&Self>) -> Vec<String> {
+                let mut changes = Vec::new();
+                $(
+                    if previous.map(|previous| &previous.$field) != Some(&self.$field) {
+                        changes.push(format!("{}={}", stringify!($field), encode(&self.$field)));
+                    }
+                )*
+                changes
+            }
+
+            fn set(&mut self, field: &str, value: &str) -> Result<(), String> {
+                match field {
+                    $(stringify!($field) => self.$field = decode(field, value)?,)*
+                    _ => return Err(format!("Unknown field `{}`", field)),
+                }
+                Ok(())
+            }
+        }
+
+        /// Turns snapshots back into the variables the splitter reads, remembering the previous tick like `State` does.
+        #[derive(Default)]
+        pub struct Playback {
+            $($field: Watcher<$ty>,)*
+        }
+        impl Playback {
+            pub fn update(&mut self, snapshot: &Snapshot) -> Variables<'_> {
+                Variables {
+                    $($field: self.$field.update(snapshot.$field),)*
+                }
+            }
+        }
+    };
+}
+
+trace_fields! {
+    map_id: i32,
+    game_progress: f64,
+    program_chips: f64,
+    game_percent: f64,
+    game_completed: f64,
+    token_recognitions: [u8; 50],
+    token_recognitions_two: [u8; 50],
+    in_cutscene: f64,
+    in_game_over: f64,
+    is_paused: f64,
+    current_boss: [u8; 100],
+    boss_track: f64,
+    boss_gallery: f64,
+}
+
+fn encode_timer(state: TimerState) -> &'static str {
+    match state {
+        TimerState::NotRunning => "not_running",
+        TimerState::Running => "running",
+        TimerState::Paused => "paused",
+        TimerState::Ended => "ended",
+    }
+}
+
+fn decode_timer(text: &str) -> Result<TimerState, String> {
+    match text {
+        "not_running" => Ok(TimerState::NotRunning),
+        "running" => Ok(TimerState::Running),
+        "paused" => Ok(TimerState::Paused),
+        "ended" => Ok(TimerState::Ended),
+        _ => Err(format!("`{}` isn't a timer state", text)),
+    }
+}
+
+/// Writes the trace one tick at a time.
+///
+/// Each line is `DT3TRACE1 <tick> <field>=<value>...` with only the fields that changed since the line before,
+/// so ticks where nothing changed don't get a line at all. Every field is named after the variable it holds,
+/// except `timer`, which is the timer state when it isn't where the splitter left it, like after the runner
+/// reset by hand. The first line is marked `start` and always has every field, since there's nothing before it
+/// to go by. A new writer is needed whenever recording stops for a while, so the next line starts over.
+#[derive(Default)]
+pub struct TraceWriter {
+    previous: Option<Snapshot>,
+    /// the timer state the splitter left behind last tick
+    expected_timer: Option<TimerState>,
+}
+impl TraceWriter {
+    /// The line for this tick, or `None` if nothing changed since the last one.
+    /// `timer` is the timer state before the splitter runs.
+    pub fn line(&mut self, tick: u64, timer: TimerState, snapshot: Snapshot) -> Option<String> {
+        let mut changes = Vec::new();
+        if self.previous.is_none() {
+            changes.push("start".to_owned());
+        }
+        if self.expected_timer != Some(timer) {
+            changes.push(format!("timer={}", encode_timer(timer)));
+        }
+        changes.extend(snapshot.changes(self.previous.as_ref()));
+        self.previous = Some(snapshot);
+        self.expected_timer = Some(timer);
+
+        if changes.is_empty() {
+            None
+        } else {
+            Some(format!("{} {} {}", TRACE_MARKER, tick, changes.join(" ")))
+        }
+    }
+
+    /// Remembers what the splitter did to the timer this tick, so it isn't mistaken for the runner's doing.
+    pub fn splitter_ran(&mut self, timer: TimerState) {
+        self.expected_timer = Some(timer);
+    }
+}
+
+/// One tick of a trace.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub tick: u64,
+    /// recording (re)started on this tick, so it has nothing to do with the frame before it
+    pub start: bool,
+    /// set when something other than the splitter changed the timer before this tick, like the runner
+    pub timer: Option<TimerState>,
+    pub snapshot: Snapshot,
+}
+
+/// Reads every trace line out of `text`, ignoring anything else in it like the rest of a log.
+/// Ticks that were skipped because nothing changed are filled back in, so every tick gets a frame,
+/// except before a `start` line, where recording was off and nothing is known about the ticks in between.
+/// A tick that doesn't go up means the splitter reconnected, and just carries on from there.
+pub fn parse(text: &str) -> Result<Vec<Frame>, String> {
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut snapshot = Snapshot::default();
+
+    for (number, line) in text.lines().enumerate() {
+        // log viewers put timestamps and the like in front of the message
+        let Some(start) = line.find(TRACE_MARKER) else {
+            continue;
+        };
+        let error = |err: String| format!("Line {}: {}", number + 1, err);
+        let mut parts = line[start + TRACE_MARKER.len()..].split_whitespace();
+
+        let tick = parts
+            .next()
+            .and_then(|tick| tick.parse::<u64>().ok())
+            .ok_or_else(|| error("Missing the tick".to_owned()))?;
+        let parts = parts.collect::<Vec<_>>();
+        let start = parts.contains(&"start");
+        if start {
+            snapshot = Snapshot::default();
+        }
+        let mut timer = None;
+        for &part in parts.iter().filter(|&&part| part != "start") {
+            let (field, value) = part
+                .split_once('=')
+                .ok_or_else(|| error(format!("`{}` isn't in the form `field=value`", part)))?;
+            if field == "timer" {
+                timer = Some(decode_timer(value).map_err(error)?);
+            } else {
+                snapshot.set(field, value).map_err(error)?;
+            }
+        }
+
+        if let Some(last) = frames.last().cloned().filter(|_| !start) {
+            for skipped in last.tick + 1..tick {
+                frames.push(Frame {
+                    tick: skipped,
+                    start: false,
+                    timer: None,
+                    snapshot: last.snapshot.clone(),
+                });
+            }
+        }
+        frames.push(Frame {
+            tick,
+            start,
+            timer,
+            snapshot: snapshot.clone(),
+        });
+    }
+
+    Ok(frames)
+}
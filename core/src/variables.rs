@@ -0,0 +1,65 @@
+use crate::{chapters::CHAPTERS, watcher::Pair};
+
+pub struct Variables<'a> {
+    pub map_id: Option<&'a Pair<i32>>,
+    pub game_progress: Option<&'a Pair<f64>>,
+    pub program_chips: Option<&'a Pair<f64>>,
+    pub game_percent: Option<&'a Pair<f64>>,
+    pub game_completed: Option<&'a Pair<f64>>,
+    pub token_recognitions: Option<&'a Pair<[u8; 50]>>,
+    pub token_recognitions_two: Option<&'a Pair<[u8; 50]>>,
+    pub in_cutscene: Option<&'a Pair<f64>>,
+    pub in_game_over: Option<&'a Pair<f64>>,
+    pub is_paused: Option<&'a Pair<f64>>,
+    pub current_boss: Option<&'a Pair<[u8; 100]>>,
+    pub boss_track: Option<&'a Pair<f64>>,
+    pub boss_gallery: Option<&'a Pair<f64>>,
+}
+impl<'a> Variables<'a> {
+    /// The name of the boss that died this tick, going by `currentBossName` being cleared.
+    pub fn defeated_boss(&self) -> Option<&'a str> {
+        let boss = self.current_boss?;
+        let old = Variables::get_as_string(&boss.old).filter(|name| !name.is_empty())?;
+        match Variables::get_as_string(&boss.current) {
+            Some(current) if !current.is_empty() => None,
+            _ => Some(old),
+        }
+    }
+
+    /// The name of the boss whose fight started this tick, going by `currentBossName` being set.
+    pub fn started_boss(&self) -> Option<&'a str> {
+        let boss = self.current_boss?;
+        let current = Variables::get_as_string(&boss.current).filter(|name| !name.is_empty())?;
+        match Variables::get_as_string(&boss.old) {
+            Some(old) if !old.is_empty() => None,
+            _ => Some(current),
+        }
+    }
+
+    /// Whether we're somewhere a value might only change for a single frame, like a boss name clearing when it dies
+    /// or `gameCompleted` being set for a frame before the epilogue goes back to the title.
+    /// Remembering a change until it's used can't help with those, since a read only ever sees one frame,
+    /// so polling faster around them is the only way not to miss them.
+    pub fn near_short_event(&self) -> bool {
+        let boss_named = self.current_boss.is_some_and(|boss| {
+            Variables::get_as_string(&boss.current).is_some_and(|name| !name.is_empty())
+        });
+        let final_chapter = CHAPTERS.last().map(|chapter| chapter.progress);
+        let game_ending = self
+            .game_progress
+            .zip(final_chapter)
+            .is_some_and(|(progress, start)| progress.current >= start);
+        boss_named || self.boss_track.is_some_and(|x| x.current != 0.0) || game_ending
+    }
+
+    /// Assumes we're not in the boss gallery if we can't tell, since that's the common case.
+    pub fn in_boss_gallery(&self) -> bool {
+        self.boss_gallery.is_some_and(|x| x.current != 0.0)
+    }
+
+    pub fn get_as_string(var: &'a [u8]) -> Option<&'a str> {
+        let null_pos = var.iter().position(|&x| x == b'\0').unwrap_or(var.len());
+
+        std::str::from_utf8(&var[0..null_pos]).ok()
+    }
+}
@@ -0,0 +1,46 @@
+/// A value as it was on the previous tick and as it is now.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Pair<T> {
+    pub old: T,
+    pub current: T,
+}
+impl<T> Pair<T> {
+    /// Whether `f` became true this tick.
+    pub fn check(&self, mut f: impl FnMut(&T) -> bool) -> bool {
+        !f(&self.old) && f(&self.current)
+    }
+}
+
+/// Remembers the last value read so it can be compared with the next one.
+/// Forgets it whenever a read fails, so a value coming back doesn't look like a change.
+#[derive(Debug, Clone)]
+pub struct Watcher<T> {
+    pair: Option<Pair<T>>,
+}
+impl<T> Default for Watcher<T> {
+    fn default() -> Self {
+        Self { pair: None }
+    }
+}
+impl<T: Copy> Watcher<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, value: Option<T>) -> Option<&Pair<T>> {
+        match (value, &mut self.pair) {
+            (Some(value), Some(pair)) => {
+                pair.old = pair.current;
+                pair.current = value;
+            }
+            (Some(value), pair) => {
+                *pair = Some(Pair {
+                    old: value,
+                    current: value,
+                });
+            }
+            (None, pair) => *pair = None,
+        }
+        self.pair.as_ref()
+    }
+}
@@ -0,0 +1,148 @@
+//! A fake host for driving the splitter one tick at a time, shared by the integration tests.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use dt3_core::{
+    replay::Action,
+    settings::SplitterSettings,
+    splitter::Splitter,
+    timer::{Logger, Timer, TimerState},
+    trace::{Playback, Snapshot},
+};
+
+/// Stands in for the runtime, writing down everything the splitter asks of it.
+pub struct Host {
+    pub state: TimerState,
+    pub game_time_paused: bool,
+    pub actions: Vec<Action>,
+    pub messages: Vec<String>,
+}
+impl Default for Host {
+    fn default() -> Self {
+        Self {
+            state: TimerState::NotRunning,
+            game_time_paused: false,
+            actions: Vec::new(),
+            messages: Vec::new(),
+        }
+    }
+}
+impl Timer for Host {
+    fn state(&self) -> TimerState {
+        self.state
+    }
+
+    // like the runtime, starting, splitting and resetting only do something when the timer is in the right state
+    fn start(&mut self) {
+        if self.state == TimerState::NotRunning {
+            self.state = TimerState::Running;
+            self.actions.push(Action::Start);
+        }
+    }
+
+    fn split(&mut self) {
+        if self.state == TimerState::Running {
+            self.actions.push(Action::Split);
+        }
+    }
+
+    fn reset(&mut self) {
+        if self.state != TimerState::NotRunning {
+            self.state = TimerState::NotRunning;
+            self.actions.push(Action::Reset);
+        }
+    }
+
+    fn pause_game_time(&mut self) {
+        self.game_time_paused = true;
+    }
+
+    fn resume_game_time(&mut self) {
+        self.game_time_paused = false;
+    }
+}
+impl Logger for Host {
+    fn print_message(&mut self, message: &str) {
+        self.messages.push(message.to_owned());
+    }
+}
+
+/// Settings written like the replay takes them, `key` to turn one on or `key=value`,
+/// with everything else at its default.
+pub fn settings(picked: &[&str]) -> SplitterSettings {
+    let store = picked
+        .iter()
+        .map(|setting| {
+            let (key, value) = setting.split_once('=').unwrap_or((setting, "true"));
+            (key.to_owned(), value.to_owned())
+        })
+        .collect::<HashMap<_, _>>();
+    SplitterSettings::load(&store)
+}
+
+/// A run the test changes one variable at a time, like the game would.
+pub struct Run {
+    pub host: Host,
+    pub splitter: Splitter,
+    pub snapshot: Snapshot,
+    playback: Playback,
+}
+impl Run {
+    /// Starts on the title screen with nothing collected yet.
+    pub fn new(settings: SplitterSettings) -> Self {
+        let mut host = Host::default();
+        let splitter = Splitter::new(settings, &mut host);
+        let mut run = Self {
+            host,
+            splitter,
+            snapshot: Snapshot {
+                map_id: Some(1),
+                game_progress: Some(0.0),
+                program_chips: Some(0.0),
+                game_percent: Some(0.0),
+                game_completed: Some(0.0),
+                token_recognitions: Some([0; 50]),
+                token_recognitions_two: Some([0; 50]),
+                in_cutscene: Some(0.0),
+                in_game_over: Some(0.0),
+                is_paused: Some(0.0),
+                current_boss: Some([0; 100]),
+                boss_track: Some(0.0),
+                boss_gallery: Some(0.0),
+            },
+            playback: Playback::default(),
+        };
+        run.tick();
+        run
+    }
+
+    /// Runs the splitter once on the current snapshot.
+    pub fn tick(&mut self) {
+        let vars = self.playback.update(&self.snapshot);
+        self.splitter.update(&vars, &mut self.host);
+    }
+
+    /// Changes the snapshot, then runs a tick on it.
+    pub fn step(&mut self, change: impl FnOnce(&mut Snapshot)) {
+        change(&mut self.snapshot);
+        self.tick();
+    }
+
+    /// Picks new game on the title screen.
+    pub fn new_game(&mut self) {
+        self.step(|s| s.map_id = Some(43));
+    }
+
+    /// Empties what the host has written down so far.
+    pub fn take_actions(&mut self) -> Vec<Action> {
+        std::mem::take(&mut self.host.actions)
+    }
+}
+
+/// `currentBossName` holding this name.
+pub fn boss(name: &str) -> [u8; 100] {
+    let mut bytes = [0; 100];
+    bytes[..name.len()].copy_from_slice(name.as_bytes());
+    bytes
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use common::{settings, Host};
+use dt3_core::{
+    memory::{FakeMemory, MemorySource, ReadError},
+    pointers::V1_5_2_4,
+    replay::Action,
+    splitter::Splitter,
+    state::State,
+};
+
+const MODULE_BASE: u32 = 0x40_0000;
+/// where the pointer to the globals instance is
+const GLOBALS: u32 = MODULE_BASE + V1_5_2_4.globals;
+
+fn offset(name: &str) -> u32 {
+    V1_5_2_4.global_offset(name, &mut Host::default()).unwrap()
+}
+
+fn global(name: &str) -> Vec<u32> {
+    vec![0x0, V1_5_2_4.globals_vars, offset(name)]
+}
+
+/// The game sitting on the title screen with a fresh save.
+fn title_screen() -> FakeMemory {
+    let mut memory = FakeMemory::new();
+    memory.write_pointer_path32(MODULE_BASE, &[V1_5_2_4.map_id], 1i32);
+    for name in [
+        "gameProgress",
+        "programChips",
+        "gamePercent",
+        "gameCompleted",
+        "inCutscene",
+        "inGameOver",
+        "isPaused",
+        "bossTrack",
+        "bossGallery",
+    ] {
+        memory.write_pointer_path32(GLOBALS, &global(name), 0.0f64);
+    }
+    for name in ["tokenRecognitions", "tokenRecognitionsTwo"] {
+        memory.write_pointer_path32(GLOBALS, &[global(name), vec![0x0]].concat(), [b'0'; 50]);
+    }
+    memory.write_pointer_path32(
+        GLOBALS,
+        &[global("currentBossName"), vec![0x0]].concat(),
+        [0u8; 100],
+    );
+    memory
+}
+
+fn state(log: &mut Host) -> State {
+    State::new(&V1_5_2_4, MODULE_BASE, GLOBALS, log)
+}
+
+#[test]
+fn pointer_paths_read_back_what_was_written() {
+    let mut memory = FakeMemory::new();
+    memory.write_pointer_path32(0x1000, &[0x10, 0x4, 0x20], 5u32);
+    memory.write_pointer_path32(0x1000, &[0x10, 0x4, 0x28], 6u32);
+
+    assert_eq!(
+        memory.read_pointer_path32::<u32>(0x1000, &[0x10, 0x4, 0x20]),
+        Ok(5)
+    );
+    // the second write followed the pointers the first one made
+    assert_eq!(
+        memory.read_pointer_path32::<u32>(0x1000, &[0x10, 0x4, 0x28]),
+        Ok(6)
+    );
+    assert_eq!(
+        memory.read_pointer_path32::<u32>(0x1000, &[0x10, 0x8, 0x20]),
+        Err(ReadError)
+    );
+    assert_eq!(
+        memory.read_pointer_path32::<u32>(0x1000, &[]),
+        Err(ReadError)
+    );
+
+    let pointer = memory.read::<u32>(0x1010).unwrap();
+    memory.remove(pointer as u64, 8);
+    assert_eq!(
+        memory.read_pointer_path32::<u32>(0x1000, &[0x10, 0x4, 0x20]),
+        Err(ReadError)
+    );
+}
+
+#[test]
+fn unreadable_variables_are_none_and_the_rest_keep_working() {
+    let mut memory = title_screen();
+    let mut log = Host::default();
+    let mut state = state(&mut log);
+
+    let vars = state.update(&memory, &mut log);
+    assert_eq!(vars.map_id.map(|x| x.current), Some(1));
+    assert_eq!(vars.game_progress.map(|x| x.current), Some(0.0));
+
+    let progress = memory
+        .read_pointer_path32::<u32>(GLOBALS, &[0x0, V1_5_2_4.globals_vars])
+        .unwrap()
+        + offset("gameProgress");
+    memory.remove(progress as u64, 8);
+    let vars = state.update(&memory, &mut log);
+    assert!(vars.game_progress.is_none());
+    assert_eq!(vars.map_id.map(|x| x.current), Some(1));
+
+    memory.write_value(progress as u64, 10.0f64);
+    let vars = state.update(&memory, &mut log);
+    // a value coming back isn't a change
+    assert_eq!(
+        vars.game_progress.map(|x| (x.old, x.current)),
+        Some((10.0, 10.0))
+    );
+
+    assert_eq!(
+        log.messages,
+        [
+            "`game_progress` can't be read since tick 2, anything depending on it is disabled",
+            "`game_progress` can be read again after being unreadable since tick 2",
+        ]
+    );
+}
+
+#[test]
+fn splits_from_game_memory() {
+    let mut memory = title_screen();
+    let mut host = Host::default();
+    let mut state = state(&mut host);
+    let mut splitter = Splitter::new(settings(&["split_on_every_chapter"]), &mut host);
+    let mut tick = |memory: &FakeMemory, host: &mut Host| {
+        let vars = state.update(memory, host);
+        splitter.update(&vars, host);
+    };
+
+    tick(&memory, &mut host);
+    memory.write_pointer_path32(MODULE_BASE, &[V1_5_2_4.map_id], 43i32);
+    tick(&memory, &mut host);
+    memory.write_pointer_path32(GLOBALS, &global("gameProgress"), 170.0f64);
+    tick(&memory, &mut host);
+    // back to the title screen, and new game on a fresh save
+    memory.write_pointer_path32(MODULE_BASE, &[V1_5_2_4.map_id], 1i32);
+    memory.write_pointer_path32(GLOBALS, &global("gameProgress"), 0.0f64);
+    tick(&memory, &mut host);
+    memory.write_pointer_path32(MODULE_BASE, &[V1_5_2_4.map_id], 43i32);
+    tick(&memory, &mut host);
+
+    assert_eq!(
+        host.actions,
+        [Action::Start, Action::Split, Action::Reset, Action::Start]
+    );
+}
@@ -0,0 +1,39 @@
+use dt3_core::pointers::PointerTable;
+
+const TABLE: PointerTable = PointerTable {
+    map_id: 0x100,
+    globals: 0x2000,
+    globals_signature: "A1 ?? ?? ?? ?? 8B 40 04",
+    globals_signature_offset: 0x1,
+    globals_vars: 0x4,
+    global_offsets: &[],
+};
+const MODULE_BASE: u32 = 0x40_0000;
+
+#[test]
+fn trusts_a_single_signature_match() {
+    assert_eq!(
+        TABLE.pick_globals(MODULE_BASE, &[0x40_3000, 0x40_3000]),
+        Ok(0x40_3000)
+    );
+}
+
+#[test]
+fn prefers_the_match_at_the_static_offset() {
+    assert_eq!(
+        TABLE.pick_globals(MODULE_BASE, &[0x40_3000, 0x40_2000, 0x40_4000]),
+        Ok(0x40_2000)
+    );
+}
+
+#[test]
+fn rejects_ambiguous_or_missing_matches() {
+    assert_eq!(
+        TABLE.pick_globals(MODULE_BASE, &[0x40_3000, 0x40_4000]),
+        Err("the signature matched 2 different pointers".to_owned())
+    );
+    assert_eq!(
+        TABLE.pick_globals(MODULE_BASE, &[]),
+        Err("the signature didn't match anything".to_owned())
+    );
+}
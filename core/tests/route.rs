@@ -0,0 +1,87 @@
+mod common;
+
+use common::{boss, settings, Run};
+use dt3_core::replay::Action;
+
+#[test]
+fn only_the_next_split_in_the_route_fires() {
+    let mut run = Run::new(settings(&[
+        "split_on_route",
+        "split_on_every_chapter",
+        "route=# comment\nboss:First Boss\n\nchapter:3\npercent:50",
+    ]));
+    run.new_game();
+    run.take_actions();
+
+    // the other split settings are ignored
+    run.step(|s| s.game_progress = Some(170.0));
+    assert!(run.take_actions().is_empty());
+
+    run.step(|s| s.current_boss = Some(boss("First Boss")));
+    run.step(|s| s.current_boss = Some([0; 100]));
+    assert_eq!(run.take_actions(), [Action::Split]);
+
+    run.step(|s| s.game_percent = Some(60.0));
+    assert!(run.take_actions().is_empty());
+    run.step(|s| s.game_progress = Some(530.0));
+    assert_eq!(run.take_actions(), [Action::Split]);
+
+    // 49.9999 is close enough to 50
+    run.step(|s| s.game_percent = Some(40.0));
+    run.step(|s| s.game_percent = Some(49.9999));
+    assert_eq!(run.take_actions(), [Action::Split]);
+    assert!(run
+        .host
+        .messages
+        .contains(&"Route split 3/3 reached: percent:50".to_owned()));
+}
+
+#[test]
+fn route_bosses_follow_the_boss_gallery_setting() {
+    for (gallery, splits) in [("false", 0), ("true", 1)] {
+        let mut run = Run::new(settings(&[
+            "split_on_route",
+            &format!("split_on_boss_gallery={}", gallery),
+            "route=boss:First Boss",
+        ]));
+        run.new_game();
+        run.take_actions();
+
+        run.step(|s| {
+            s.boss_gallery = Some(1.0);
+            s.current_boss = Some(boss("First Boss"));
+        });
+        run.step(|s| s.current_boss = Some([0; 100]));
+        assert_eq!(run.take_actions().len(), splits);
+    }
+}
+
+#[test]
+fn empty_route_falls_back_to_the_other_settings() {
+    let mut run = Run::new(settings(&["split_on_route", "split_on_every_chapter"]));
+    assert!(run.host.messages.contains(
+        &"`split_on_route` is on but there's no route, so the other split settings are used instead"
+            .to_owned()
+    ));
+    run.new_game();
+    run.take_actions();
+
+    run.step(|s| s.game_progress = Some(170.0));
+    assert_eq!(run.take_actions(), [Action::Split]);
+}
+
+#[test]
+fn changing_the_route_reads_it_again() {
+    let mut run = Run::new(settings(&["split_on_route", "route=chapter:1"]));
+    assert!(run.host.messages.contains(
+        &"Couldn't read the route: Chapter 1 starts along with the timer, so it can't be split on"
+            .to_owned()
+    ));
+
+    let picked = settings(&["split_on_route", "route=chapter:2"]);
+    run.splitter.set_settings(picked, &mut run.host);
+    run.new_game();
+    run.take_actions();
+    run.step(|s| s.game_progress = Some(170.0));
+    assert_eq!(run.take_actions(), [Action::Split]);
+}
@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use dt3_core::settings::SplitterSettings;
+
+#[test]
+fn registers_catalogue_settings_under_their_full_keys() {
+    let mut store = HashMap::new();
+    SplitterSettings::register(&mut store);
+
+    for key in [
+        "split_on_chapter_2",
+        "split_on_chapter_21",
+        "split_on_achievement_1",
+        "split_on_achievement_100",
+        "split_on_program_chips_10",
+    ] {
+        assert_eq!(store.get(key).map(String::as_str), Some("false"), "{}", key);
+    }
+    assert!(!store.contains_key("split_on_chapter_1"));
+    assert!(!store.contains_key("split_on_achievement_0"));
+}
+
+#[test]
+fn lists_the_keys_that_changed() {
+    let mut store = HashMap::new();
+    SplitterSettings::register(&mut store);
+    let before = SplitterSettings::load(&store);
+
+    store.insert("split_on_chapter_5".to_owned(), "true".to_owned());
+    store.insert("split_on_every_chapter".to_owned(), "true".to_owned());
+    let after = SplitterSettings::load(&store);
+
+    assert!(after.split_on_chapters.is_enabled(3));
+    assert_eq!(
+        after.changed_keys(&before),
+        ["split_on_every_chapter", "split_on_chapter_5"]
+    );
+}
+
+#[test]
+fn defaults_match_loading_from_an_empty_store() {
+    assert!(SplitterSettings::default() == SplitterSettings::load(&HashMap::new()));
+}
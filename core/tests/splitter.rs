@@ -0,0 +1,468 @@
+mod common;
+
+use common::{boss, settings, Run};
+use dt3_core::{replay::Action, settings::SplitterSettings, timer::TimerState};
+
+#[test]
+fn new_game_starts_the_timer() {
+    let mut run = Run::new(SplitterSettings::default());
+    assert!(run.take_actions().is_empty());
+
+    run.new_game();
+    assert_eq!(run.take_actions(), [Action::Start]);
+    assert_eq!(run.host.state, TimerState::Running);
+}
+
+#[test]
+fn continuing_only_starts_with_start_on_continue() {
+    let mut run = Run::new(SplitterSettings::default());
+    run.step(|s| s.map_id = Some(100));
+    assert!(run.take_actions().is_empty());
+
+    let mut run = Run::new(settings(&["start_on_continue"]));
+    run.step(|s| s.map_id = Some(100));
+    assert_eq!(run.take_actions(), [Action::Start]);
+}
+
+#[test]
+fn splits_once_per_chapter() {
+    let mut run = Run::new(settings(&["split_on_every_chapter"]));
+    run.new_game();
+    run.take_actions();
+
+    run.step(|s| s.game_progress = Some(170.0));
+    assert_eq!(run.take_actions(), [Action::Split]);
+
+    // loading an older save and playing through it again doesn't split twice
+    run.step(|s| s.game_progress = Some(100.0));
+    run.step(|s| s.game_progress = Some(170.0));
+    assert!(run.take_actions().is_empty());
+}
+
+#[test]
+fn simultaneous_splits_are_combined_unless_queued() {
+    let enabled = ["split_on_every_chapter", "split_on_every_map_change"];
+
+    let mut run = Run::new(settings(&enabled));
+    run.new_game();
+    run.take_actions();
+    run.step(|s| {
+        s.map_id = Some(100);
+        s.game_progress = Some(170.0);
+    });
+    run.tick();
+    assert_eq!(run.take_actions(), [Action::Split]);
+
+    let mut run = Run::new(settings(
+        &[enabled.as_slice(), &["queue_simultaneous_splits"]].concat(),
+    ));
+    run.new_game();
+    run.take_actions();
+    run.step(|s| {
+        s.map_id = Some(100);
+        s.game_progress = Some(170.0);
+    });
+    run.tick();
+    assert_eq!(run.take_actions(), [Action::Split, Action::Split]);
+}
+
+#[test]
+fn retrying_a_boss_only_splits_on_the_first_attempt() {
+    let mut run = Run::new(settings(&[
+        "split_on_every_boss_start",
+        "split_on_every_boss",
+    ]));
+    run.new_game();
+    run.take_actions();
+
+    run.step(|s| s.current_boss = Some(boss("Boss")));
+    assert_eq!(run.take_actions(), [Action::Split]);
+    run.step(|s| s.current_boss = Some([0; 100]));
+    assert_eq!(run.take_actions(), [Action::Split]);
+
+    run.step(|s| s.current_boss = Some(boss("Boss")));
+    run.step(|s| s.current_boss = Some([0; 100]));
+    assert!(run.take_actions().is_empty());
+}
+
+#[test]
+fn boss_gallery_only_splits_when_enabled() {
+    for (enabled, splits) in [(false, 0), (true, 1)] {
+        let mut run = Run::new(if enabled {
+            settings(&["split_on_boss_gallery"])
+        } else {
+            SplitterSettings::default()
+        });
+        run.new_game();
+        run.take_actions();
+
+        run.step(|s| {
+            s.boss_gallery = Some(1.0);
+            s.current_boss = Some(boss("Boss"));
+        });
+        assert_eq!(run.take_actions().len(), splits);
+        run.step(|s| s.current_boss = Some([0; 100]));
+        assert_eq!(run.take_actions().len(), splits);
+    }
+}
+
+#[test]
+fn title_screen_resets_only_when_enabled() {
+    let mut run = Run::new(SplitterSettings::default());
+    run.new_game();
+    run.step(|s| s.map_id = Some(1));
+    assert_eq!(run.take_actions(), [Action::Start]);
+
+    let mut run = Run::new(settings(&["reset_on_titlescreen"]));
+    run.new_game();
+    run.step(|s| s.map_id = Some(1));
+    assert_eq!(run.take_actions(), [Action::Start, Action::Reset]);
+}
+
+#[test]
+fn new_game_restarts_a_running_timer() {
+    let mut run = Run::new(SplitterSettings::default());
+    run.new_game();
+    run.step(|s| s.map_id = Some(1));
+    run.new_game();
+    assert_eq!(
+        run.take_actions(),
+        [Action::Start, Action::Reset, Action::Start]
+    );
+}
+
+#[test]
+fn pauses_game_time_while_paused() {
+    let mut run = Run::new(settings(&["remove_pause_time"]));
+    run.new_game();
+    run.step(|s| s.is_paused = Some(1.0));
+    assert!(run.host.game_time_paused);
+    run.step(|s| s.is_paused = Some(0.0));
+    assert!(!run.host.game_time_paused);
+
+    // cutscenes are only removed when asked for
+    run.step(|s| s.in_cutscene = Some(1.0));
+    assert!(!run.host.game_time_paused);
+}
+
+#[test]
+fn queued_splits_happen_in_priority_order() {
+    let mut run = Run::new(settings(&[
+        "split_on_every_map_change",
+        "split_on_every_chapter",
+        "queue_simultaneous_splits",
+    ]));
+    run.new_game();
+    run.step(|s| {
+        s.map_id = Some(100);
+        s.game_progress = Some(170.0);
+    });
+    run.tick();
+
+    let splits = run
+        .host
+        .messages
+        .iter()
+        .filter(|message| message.starts_with("Splitting due to"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        splits,
+        [
+            "Splitting due to `split_on_every_chapter` (1 more queued)",
+            "Splitting due to `split_on_every_map_change` (0 more queued)",
+        ]
+    );
+}
+
+#[test]
+fn each_percentage_splits_once_with_some_slack() {
+    let mut run = Run::new(settings(&["split_every_ten_percent"]));
+    run.new_game();
+    run.take_actions();
+
+    run.step(|s| s.game_percent = Some(9.9999));
+    assert_eq!(run.take_actions(), [Action::Split]);
+
+    // loading an older save doesn't split again on the way back up
+    run.step(|s| s.game_percent = Some(5.0));
+    run.step(|s| s.game_percent = Some(10.0));
+    assert!(run.take_actions().is_empty());
+}
+
+#[test]
+fn each_program_chip_count_splits_once() {
+    let mut run = Run::new(settings(&["split_on_every_program_chip"]));
+    run.new_game();
+    run.take_actions();
+
+    run.step(|s| s.program_chips = Some(2.0));
+    assert_eq!(
+        run.host
+            .messages
+            .iter()
+            .filter(|message| message.starts_with("Reached"))
+            .collect::<Vec<_>>(),
+        ["Reached 1 program chips", "Reached 2 program chips"]
+    );
+    assert_eq!(run.take_actions(), [Action::Split]);
+
+    run.step(|s| s.program_chips = Some(1.0));
+    run.step(|s| s.program_chips = Some(2.0));
+    assert!(run.take_actions().is_empty());
+}
+
+#[test]
+fn garbage_program_chip_counts_are_ignored() {
+    let mut run = Run::new(settings(&["split_on_program_chips_1"]));
+    run.new_game();
+    run.take_actions();
+
+    for garbage in [f64::MAX, 3e7, f64::NAN, 0.0] {
+        run.step(|s| s.program_chips = Some(garbage));
+    }
+    assert!(run.take_actions().is_empty());
+    assert!(!run
+        .host
+        .messages
+        .iter()
+        .any(|message| message.starts_with("Reached")));
+
+    // the real count still splits afterwards
+    run.step(|s| s.program_chips = Some(1.0));
+    assert_eq!(run.take_actions(), [Action::Split]);
+}
+
+#[test]
+fn chapter_settings_are_numbered_from_the_second_chapter() {
+    let mut run = Run::new(settings(&["split_on_chapter_3"]));
+    run.new_game();
+    run.take_actions();
+
+    // chapter 2 starts at 160
+    run.step(|s| s.game_progress = Some(170.0));
+    assert!(run.take_actions().is_empty());
+    // chapter 3 starts at 530
+    run.step(|s| s.game_progress = Some(530.0));
+    assert_eq!(run.take_actions(), [Action::Split]);
+}
+
+#[test]
+fn achievement_settings_are_numbered_from_one() {
+    let mut run = Run::new(settings(&["split_on_achievement_51"]));
+    run.new_game();
+    run.take_actions();
+
+    // the first achievement of the second array
+    run.step(|s| {
+        let mut tokens = [b'0'; 50];
+        tokens[0] = b'1';
+        s.token_recognitions_two = Some(tokens);
+    });
+    assert_eq!(run.take_actions(), [Action::Split]);
+    assert!(run
+        .host
+        .messages
+        .contains(&"Unlocked achievement 51 (Token Recognition #51)".to_owned()));
+}
+
+#[test]
+fn splits_on_listed_bosses_only() {
+    let mut run = Run::new(settings(&[
+        "split_on_every_boss_start=false",
+        "split_on_every_boss=false",
+        "split_on_bosses=First Boss\n  Second Boss  \n",
+    ]));
+    run.new_game();
+    run.take_actions();
+
+    for (name, splits) in [("Second Boss", 1), ("Other Boss", 0)] {
+        run.step(|s| s.current_boss = Some(boss(name)));
+        run.step(|s| s.current_boss = Some([0; 100]));
+        assert_eq!(run.take_actions().len(), splits, "{}", name);
+    }
+    assert!(run
+        .host
+        .messages
+        .contains(&"Defeated `Other Boss`, which isn't listed in `split_on_bosses`".to_owned()));
+}
+
+#[test]
+fn custom_map_transitions_can_split_once() {
+    let mut run = Run::new(settings(&[
+        "custom_map_transitions=# a comment\nonce 200!150",
+    ]));
+    run.new_game();
+    run.take_actions();
+
+    // coming from an excluded map doesn't count
+    run.step(|s| s.map_id = Some(150));
+    run.step(|s| s.map_id = Some(200));
+    assert!(run.take_actions().is_empty());
+
+    run.step(|s| s.map_id = Some(100));
+    run.step(|s| s.map_id = Some(200));
+    assert_eq!(run.take_actions(), [Action::Split]);
+
+    run.step(|s| s.map_id = Some(100));
+    run.step(|s| s.map_id = Some(200));
+    assert!(run.take_actions().is_empty());
+}
+
+#[test]
+fn unreadable_custom_map_transitions_are_logged() {
+    let run = Run::new(settings(&["custom_map_transitions=100>nowhere"]));
+    assert!(run
+        .host
+        .messages
+        .contains(&"Couldn't read `custom_map_transitions`: Unknown map `nowhere`".to_owned()));
+}
+
+/// The reasons the splitter gave for splitting, in order.
+fn split_messages(run: &Run) -> Vec<&str> {
+    run.host
+        .messages
+        .iter()
+        .filter(|message| message.starts_with("Splitting due to"))
+        .map(String::as_str)
+        .collect()
+}
+
+#[test]
+fn one_boss_splits_once_even_when_two_settings_want_it() {
+    for queued in [false, true] {
+        let mut run = Run::new(settings(&[
+            "split_on_every_boss_start=false",
+            "split_on_bosses=Boss",
+            &format!("queue_simultaneous_splits={}", queued),
+        ]));
+        run.new_game();
+        run.take_actions();
+
+        run.step(|s| s.current_boss = Some(boss("Boss")));
+        run.step(|s| s.current_boss = Some([0; 100]));
+        run.tick();
+        assert_eq!(run.take_actions(), [Action::Split], "queued: {}", queued);
+        assert_eq!(split_messages(&run).len(), 1);
+    }
+}
+
+#[test]
+fn one_percentage_splits_once_even_when_two_settings_want_it() {
+    let mut run = Run::new(settings(&[
+        "split_every_ten_percent",
+        "queue_simultaneous_splits",
+    ]));
+    run.new_game();
+    run.step(|s| s.game_percent = Some(95.0));
+    for _ in 0..10 {
+        run.tick();
+    }
+    run.take_actions();
+
+    run.step(|s| s.game_percent = Some(100.0));
+    run.tick();
+    assert_eq!(run.take_actions(), [Action::Split]);
+    assert_eq!(
+        split_messages(&run).last(),
+        Some(&"Splitting due to `split_every_ten_percent` (0 more queued)")
+    );
+}
+
+#[test]
+fn split_priority_picks_the_winner_and_the_queue_order() {
+    let mut run = Run::new(settings(&[
+        "split_on_every_map_change",
+        "split_on_every_chapter",
+        "queue_simultaneous_splits",
+        "split_priority=split_on_every_map_change",
+    ]));
+    run.new_game();
+    run.step(|s| {
+        s.map_id = Some(100);
+        s.game_progress = Some(170.0);
+    });
+    run.tick();
+    assert_eq!(
+        split_messages(&run),
+        [
+            "Splitting due to `split_on_every_map_change` (1 more queued)",
+            "Splitting due to `split_on_every_chapter` (0 more queued)",
+        ]
+    );
+
+    let mut run = Run::new(settings(&[
+        "split_on_every_boss_start=false",
+        "split_on_bosses=Boss",
+        "split_priority=split_on_bosses",
+    ]));
+    run.new_game();
+    run.step(|s| s.current_boss = Some(boss("Boss")));
+    run.step(|s| s.current_boss = Some([0; 100]));
+    assert_eq!(split_messages(&run), ["Splitting due to `split_on_bosses`"]);
+}
+
+#[test]
+fn unreadable_split_priority_is_logged() {
+    let run = Run::new(settings(&["split_priority=split_on_everything"]));
+    assert!(run.host.messages.contains(
+        &"Couldn't read `split_priority`: Unknown split setting `split_on_everything`".to_owned()
+    ));
+}
+
+#[test]
+fn percentages_can_have_decimals() {
+    let mut run = Run::new(settings(&["split_on_percent=66.6,\n33.3"]));
+    run.new_game();
+    run.take_actions();
+
+    run.step(|s| s.game_percent = Some(33.0));
+    assert!(run.take_actions().is_empty());
+    run.step(|s| s.game_percent = Some(33.3));
+    assert_eq!(run.take_actions(), [Action::Split]);
+    run.step(|s| s.game_percent = Some(70.0));
+    assert_eq!(run.take_actions(), [Action::Split]);
+    assert!(run
+        .host
+        .messages
+        .contains(&"Reached 66.6% items".to_owned()));
+}
+
+#[test]
+fn unreadable_percentages_are_logged() {
+    let run = Run::new(settings(&["split_on_percent=50, 150"]));
+    assert!(run.host.messages.contains(
+        &"Couldn't read `split_on_percent`: `150` isn't a percentage above 0 and up to 100"
+            .to_owned()
+    ));
+}
+
+#[test]
+fn sacred_temple_splits_on_every_entry() {
+    let mut run = Run::new(settings(&["split_on_sacred_temple"]));
+    run.new_game();
+    run.take_actions();
+
+    for _ in 0..2 {
+        run.step(|s| s.map_id = Some(100));
+        run.step(|s| s.map_id = Some(236));
+    }
+    assert_eq!(run.take_actions(), [Action::Split, Action::Split]);
+
+    // but not when coming back from its exit map
+    run.step(|s| s.map_id = Some(245));
+    run.step(|s| s.map_id = Some(236));
+    assert!(run.take_actions().is_empty());
+}
+
+#[test]
+fn custom_map_transitions_split_every_time_by_default() {
+    let mut run = Run::new(settings(&["custom_map_transitions=100>200"]));
+    run.new_game();
+    run.take_actions();
+
+    for _ in 0..2 {
+        run.step(|s| s.map_id = Some(100));
+        run.step(|s| s.map_id = Some(200));
+    }
+    assert_eq!(run.take_actions(), [Action::Split, Action::Split]);
+}
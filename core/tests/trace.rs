@@ -0,0 +1,104 @@
+mod common;
+
+use std::fs;
+
+use common::{boss, settings};
+use dt3_core::{
+    replay,
+    timer::TimerState,
+    trace::{self, Snapshot, TraceWriter},
+};
+
+#[test]
+fn traces_read_back_what_was_written() {
+    let first = Snapshot {
+        map_id: Some(1),
+        game_progress: Some(0.1 + 0.2),
+        current_boss: Some(boss("Boss with spaces")),
+        token_recognitions: Some([b'0'; 50]),
+        ..Snapshot::default()
+    };
+    let second = Snapshot {
+        map_id: Some(43),
+        game_progress: None,
+        ..first.clone()
+    };
+
+    let mut writer = TraceWriter::default();
+    let mut log = Vec::new();
+    log.extend(writer.line(1, TimerState::NotRunning, first.clone()));
+    writer.splitter_ran(TimerState::NotRunning);
+    // nothing changed, so there's no line
+    log.extend(writer.line(2, TimerState::NotRunning, first.clone()));
+    writer.splitter_ran(TimerState::Running);
+    log.extend(writer.line(4, TimerState::Running, second.clone()));
+    writer.splitter_ran(TimerState::Running);
+    // the runner reset by hand
+    log.extend(writer.line(5, TimerState::NotRunning, second.clone()));
+    assert_eq!(log.len(), 3);
+    assert!(log[0].starts_with("DT3TRACE1 1 start timer=not_running map_id=1 "));
+    assert_eq!(log[1], "DT3TRACE1 4 map_id=43 game_progress=-");
+
+    let frames = trace::parse(&log.join("\nsomething else in the log\n")).unwrap();
+    let read = frames
+        .iter()
+        .map(|frame| (frame.tick, frame.timer, frame.snapshot.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        read,
+        [
+            (1, Some(TimerState::NotRunning), first.clone()),
+            (2, None, first.clone()),
+            (3, None, first),
+            (4, None, second.clone()),
+            (5, Some(TimerState::NotRunning), second),
+        ]
+    );
+}
+
+#[test]
+fn replays_the_checked_in_trace() {
+    let text = fs::read_to_string("tests/traces/new_game.trace").unwrap();
+    let expected = fs::read_to_string("tests/traces/new_game.expected").unwrap();
+    let picked = text
+        .lines()
+        .find_map(|line| line.strip_prefix("# settings:"))
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<_>>();
+
+    let report = replay::replay(&trace::parse(&text).unwrap(), settings(&picked));
+    let actions = report
+        .actions
+        .iter()
+        .map(|(tick, action)| format!("{} {}\n", tick, action))
+        .collect::<String>();
+    assert_eq!(actions, expected);
+}
+
+#[test]
+fn recording_again_after_a_gap_starts_over() {
+    let snapshot = |map_id| Snapshot {
+        map_id: Some(map_id),
+        game_progress: Some(0.0),
+        ..Snapshot::default()
+    };
+
+    let mut writer = TraceWriter::default();
+    let mut log = Vec::new();
+    log.extend(writer.line(1, TimerState::NotRunning, snapshot(1)));
+    // `record_trace` was turned off and on again, which makes a new writer
+    let mut writer = TraceWriter::default();
+    log.extend(writer.line(10, TimerState::NotRunning, snapshot(43)));
+
+    let frames = trace::parse(&log.join("\n")).unwrap();
+    let read = frames
+        .iter()
+        .map(|frame| (frame.tick, frame.start, frame.snapshot.map_id))
+        .collect::<Vec<_>>();
+    assert_eq!(read, [(1, true, Some(1)), (10, true, Some(43))]);
+
+    // going from map 1 to 43 would look like a new game if the gap wasn't there
+    let report = replay::replay(&frames, settings(&[]));
+    assert!(report.actions.is_empty());
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use common::boss;
+use dt3_core::trace::{Playback, Snapshot};
+
+fn near_short_event(snapshot: Snapshot) -> bool {
+    Playback::default().update(&snapshot).near_short_event()
+}
+
+#[test]
+fn polls_faster_during_boss_fights_and_the_final_chapter() {
+    assert!(!near_short_event(Snapshot {
+        current_boss: Some([0; 100]),
+        boss_track: Some(0.0),
+        game_progress: Some(5000.0),
+        ..Snapshot::default()
+    }));
+    assert!(near_short_event(Snapshot {
+        current_boss: Some(boss("Boss")),
+        ..Snapshot::default()
+    }));
+    assert!(near_short_event(Snapshot {
+        boss_track: Some(1.0),
+        ..Snapshot::default()
+    }));
+    // the epilogue sets `gameCompleted` for a single frame
+    assert!(near_short_event(Snapshot {
+        game_progress: Some(5510.0),
+        ..Snapshot::default()
+    }));
+}
@@ -0,0 +1,41 @@
+use dt3_core::{
+    settings::SplitterSettings,
+    timer::Timer,
+    trace::{Snapshot, TraceWriter},
+    variables::Variables,
+};
+
+use crate::runtime::Runtime;
+
+/// Logs a trace line every tick something changed while `record_trace` is on, so a session can be replayed later.
+#[derive(Default)]
+pub struct TraceRecorder {
+    writer: TraceWriter,
+    /// counts every tick, recording or not, so the ticks in a trace line up with the rest of the log
+    tick: u64,
+}
+impl TraceRecorder {
+    /// Has to run before the splitter, since the trace holds the timer state the splitter saw.
+    pub fn update(&mut self, vars: &Variables, settings: &SplitterSettings) {
+        self.tick += 1;
+        if !settings.record_trace {
+            // starting again later writes every field, so the trace can be read from there on its own
+            self.writer = TraceWriter::default();
+            return;
+        }
+
+        if let Some(line) = self
+            .writer
+            .line(self.tick, Runtime.state(), Snapshot::new(vars))
+        {
+            asr::print_message(&line);
+        }
+    }
+
+    /// Has to run after the splitter, so the timer changes it made aren't written down as the runner's.
+    pub fn after_splitter(&mut self, settings: &SplitterSettings) {
+        if settings.record_trace {
+            self.writer.splitter_ran(Runtime.state());
+        }
+    }
+}
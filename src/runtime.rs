@@ -0,0 +1,92 @@
+use asr::{
+    settings::{gui, Map},
+    Address, Process,
+};
+use dt3_core::{
+    memory::{MemorySource, ReadError},
+    settings::SettingsStore,
+    timer::{Logger, Timer, TimerState},
+};
+
+/// The timer and log of the autosplitting runtime we're loaded into.
+pub struct Runtime;
+impl Timer for Runtime {
+    fn state(&self) -> TimerState {
+        match asr::timer::state() {
+            asr::timer::TimerState::NotRunning => TimerState::NotRunning,
+            asr::timer::TimerState::Running => TimerState::Running,
+            asr::timer::TimerState::Paused => TimerState::Paused,
+            asr::timer::TimerState::Ended => TimerState::Ended,
+        }
+    }
+
+    fn start(&mut self) {
+        asr::timer::start();
+    }
+
+    fn split(&mut self) {
+        asr::timer::split();
+    }
+
+    fn reset(&mut self) {
+        asr::timer::reset();
+    }
+
+    fn pause_game_time(&mut self) {
+        asr::timer::pause_game_time();
+    }
+
+    fn resume_game_time(&mut self) {
+        asr::timer::resume_game_time();
+    }
+}
+impl Logger for Runtime {
+    fn print_message(&mut self, message: &str) {
+        asr::print_message(message);
+    }
+}
+
+/// The settings the runner picked in the runtime, as of when they were loaded.
+pub struct RuntimeSettings {
+    map: Map,
+}
+impl RuntimeSettings {
+    pub fn load() -> Self {
+        Self { map: Map::load() }
+    }
+}
+impl SettingsStore for RuntimeSettings {
+    fn add_bool(&mut self, key: &str, description: &str, default: bool) {
+        gui::add_bool(key, description, default);
+    }
+
+    fn get_bool(&self, key: &str) -> Option<bool> {
+        self.map.get(key).and_then(|value| value.get_bool())
+    }
+
+    // the runtime has no text box, so text is put into the settings of the layout by hand
+    fn add_text(&mut self, key: &str, description: &str, _default: &str) {
+        gui::add_title(
+            key,
+            &format!(
+                "{}\nSet `{}` in the layout file to change it.",
+                description, key
+            ),
+            0,
+        );
+    }
+
+    fn get_text(&self, key: &str) -> Option<String> {
+        self.map.get(key).and_then(|value| value.get_string())
+    }
+}
+
+/// The game's memory, read through the runtime.
+pub struct ProcessMemory<'a>(pub &'a Process);
+impl MemorySource for ProcessMemory<'_> {
+    fn read_into_buf(&self, address: u64, buf: &mut [u8]) -> Result<(), ReadError> {
+        self.0
+            .read_into_buf(Address::new(address), buf)
+            .map_err(|_| ReadError)
+    }
+}
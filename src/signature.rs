@@ -0,0 +1,66 @@
+use asr::{Address, Process};
+
+/// How much of the module we read per call when scanning.
+const CHUNK_SIZE: u64 = 0x10000;
+
+/// A byte pattern like `A1 ?? ?? ?? ?? 8B 40 04` where `??` matches any byte.
+pub struct Signature {
+    bytes: Vec<Option<u8>>,
+}
+impl Signature {
+    pub fn parse(pattern: &str) -> Option<Self> {
+        let bytes = pattern
+            .split_whitespace()
+            .map(|byte| match byte {
+                "??" | "?" => Some(None),
+                _ => u8::from_str_radix(byte, 16).ok().map(Some),
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        if bytes.is_empty() {
+            None
+        } else {
+            Some(Self { bytes })
+        }
+    }
+
+    fn matches(&self, haystack: &[u8]) -> bool {
+        self.bytes
+            .iter()
+            .zip(haystack)
+            .all(|(pattern, byte)| match pattern {
+                Some(p) => p == byte,
+                None => true,
+            })
+    }
+
+    /// Returns the address of every match in `[start, start + len)`.
+    /// Chunks that can't be read are skipped, since modules usually have a few unmapped gaps.
+    pub fn scan(&self, process: &Process, start: Address, len: u64) -> Vec<Address> {
+        let mut found = Vec::new();
+        // chunks overlap by the pattern length so matches straddling two chunks aren't missed
+        let overlap = self.bytes.len() as u64 - 1;
+        let mut buf = vec![0; (CHUNK_SIZE + overlap) as usize];
+        let mut offset = 0;
+
+        while offset < len {
+            let size = (CHUNK_SIZE + overlap).min(len - offset) as usize;
+            let chunk = &mut buf[..size];
+
+            if process
+                .read_into_buf(Address::new(start.value() + offset), chunk)
+                .is_ok()
+            {
+                for i in 0..size.saturating_sub(overlap as usize) {
+                    if self.matches(&chunk[i..]) {
+                        found.push(Address::new(start.value() + offset + i as u64));
+                    }
+                }
+            }
+
+            offset += CHUNK_SIZE;
+        }
+
+        found
+    }
+}
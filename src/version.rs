@@ -0,0 +1,136 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use asr::{Address, Process};
+use dt3_core::pointers::{self, PointerTable};
+
+use crate::signature::Signature;
+
+pub struct GameVersion {
+    /// version shown in the logs
+    pub name: &'static str,
+    /// name of the executable this build ships as
+    pub process_name: &'static str,
+    /// name of the main module, used to find where it's loaded
+    pub module_name: &'static str,
+    /// size of the main module in bytes, which tells apart builds that ship under the same name
+    /// `None` until it's been measured for the build, in which case any size is accepted.
+    pub module_size: Option<u64>,
+    pub pointers: PointerTable,
+}
+
+/// Every build of the game we know the offsets for.
+/// To support a new build add an entry here; the first one with a running process of the right size wins.
+pub const SUPPORTED_VERSIONS: &[GameVersion] = &[GameVersion {
+    name: "v1.5.2.4",
+    process_name: "DT3_v1.5.2.4",
+    module_name: "DT3_v1.5.2.4.exe",
+    // not measured yet, the splitter logs it on attach
+    module_size: None,
+    pointers: pointers::V1_5_2_4,
+}];
+
+impl GameVersion {
+    /// Tries to attach to any supported build of the game.
+    pub fn attach() -> Option<(&'static GameVersion, Process)> {
+        SUPPORTED_VERSIONS.iter().find_map(|version| {
+            let process = Process::attach(version.process_name)?;
+            version.matches(&process).then_some((version, process))
+        })
+    }
+
+    /// Whether the main module is the size this build's is, trusting the process name if we can't tell.
+    fn matches(&self, process: &Process) -> bool {
+        // attaching is retried every tick, so only say why we didn't once per size
+        static LOGGED_SIZE: AtomicU64 = AtomicU64::new(0);
+
+        let Ok(size) = process.get_module_size(self.module_name) else {
+            return true;
+        };
+        let first_time = LOGGED_SIZE.swap(size, Ordering::Relaxed) != size;
+        match self.module_size {
+            Some(expected) if expected != size => {
+                if first_time {
+                    asr::print_message(&format!(
+                        "{} is {:#X} bytes instead of the {:#X} of {}, so it's a build we don't know",
+                        self.module_name, size, expected, self.name
+                    ));
+                }
+                false
+            }
+            Some(_) => true,
+            None => {
+                asr::print_message(&format!(
+                    "{} is {:#X} bytes, which isn't checked for {} yet",
+                    self.module_name, size, self.name
+                ));
+                true
+            }
+        }
+    }
+
+    /// Finds the absolute address of the globals instance pointer by scanning the main module.
+    /// Falls back to the static offset from the pointer table unless exactly one plausible pointer turns up.
+    pub fn find_globals(&self, process: &Process, module_base: Address) -> u32 {
+        let fallback = module_base.value() as u32 + self.pointers.globals;
+
+        let module_size = match process.get_module_size(self.module_name) {
+            Ok(size) => size,
+            Err(_) => {
+                asr::print_message(&format!(
+                    "Couldn't get the size of {}, using the static globals offset",
+                    self.module_name
+                ));
+                return fallback;
+            }
+        };
+        let signature = match Signature::parse(self.pointers.globals_signature) {
+            Some(signature) => signature,
+            None => {
+                asr::print_message(
+                    "The globals signature is malformed, using the static globals offset",
+                );
+                return fallback;
+            }
+        };
+
+        let module_end = module_base.value() + module_size;
+        let candidates = signature
+            .scan(process, module_base, module_size)
+            .into_iter()
+            .filter_map(|address| {
+                process
+                    .read::<u32>(Address::new(
+                        address.value() + self.pointers.globals_signature_offset as u64,
+                    ))
+                    .ok()
+            })
+            // the pointer has to live inside the module, and has to lead to a readable variable array
+            .filter(|&globals| {
+                (module_base.value()..module_end).contains(&(globals as u64))
+                    && process
+                        .read_pointer_path32::<u32>(globals, &[0x0, self.pointers.globals_vars])
+                        .is_ok()
+            })
+            .collect::<Vec<_>>();
+
+        match self
+            .pointers
+            .pick_globals(module_base.value() as u32, &candidates)
+        {
+            Ok(globals) => {
+                asr::print_message(&format!(
+                    "Found the globals pointer at {:#X} by signature",
+                    globals
+                ));
+                globals
+            }
+            Err(err) => {
+                asr::print_message(&format!(
+                    "Couldn't find the globals pointer by signature since {}, using the static globals offset",
+                    err
+                ));
+                fallback
+            }
+        }
+    }
+}